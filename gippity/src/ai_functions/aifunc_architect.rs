@@ -11,6 +11,7 @@ pub fn print_project_scope(_project_description: &str) {
   ///     "is_crud_required": bool, // true if site needs CRUD functionality
   ///     "is_user_login_and_logout": bool // true if site needs users to be able to log in and log out
   ///     "is_external_urls_required": bool // true if site needs to fetch data from third part providers
+  ///     "storage": "json" | "sqlite" | "sled" // json for small or demo sites, sqlite for relational data that must scale, sled for simple key value data
   ///   }
   /// Example 1:
   ///   user_request = "I need a full stack website that accepts users and gets stock price data"
@@ -19,6 +20,7 @@ pub fn print_project_scope(_project_description: &str) {
   ///     "is_crud_required": true
   ///     "is_user_login_and_logout": true
   ///     "is_external_urls_required": bool true
+  ///     "storage": "sqlite"
   ///   }
   /// Example 2:
   ///   user_request = "I need a simple TODO app"
@@ -27,6 +29,7 @@ pub fn print_project_scope(_project_description: &str) {
   ///     "is_crud_required": true
   ///     "is_user_login_and_logout": false
   ///     "is_external_urls_required": bool false
+  ///     "storage": "json"
   ///   }
   println!(OUTPUT)
 }
@@ -6,13 +6,68 @@ pub fn print_backend_webserver_code(_project_description_and_template: &str) {
   /// INPUT: Takes in a PROJECT_DESCRIPTION and CODE_TEMPLATE for a website backend build
   /// FUNCTION: Takes an existing set of code marked as CODE_TEMPLATE and updates or re-writes it to work for the purpose in the PROJECT_DESCRIPTION
   /// IMPORTANT: The following libraries are already installed
-  ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, actix_cors
+  ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, actix_cors, plus any crates listed in EXTRA_CRATES
   /// Therefore, this function can only work with code from the standard Rust library or the above as per shown in the CODE_TEMPLATE
+  /// IMPORTANT: The server must bind to the port in the PORT environment variable, falling back to 8080, as shown in the CODE_TEMPLATE
   /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
   println!(OUTPUT)
 }
 
 
+#[function_to_string]
+pub fn print_backend_crates(_project_description_and_allowlist: &str) {
+  /// INPUT: Takes in a PROJECT_DESCRIPTION for a website backend build and an ALLOWED_CRATES list of Rust crates with what each is for
+  /// FUNCTION: Picks the crates from ALLOWED_CRATES the webserver needs on top of what is already installed
+  ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, actix_cors
+  ///   Only picks a crate when the PROJECT_DESCRIPTION clearly needs it, and picks none if nothing extra is needed
+  /// FORMAT: Prints a JSON list of crate names exactly as written in ALLOWED_CRATES
+  /// OUTPUT EXAMPLE: ["chrono", "argon2"]
+  /// IMPORTANT: Only prints the JSON list. No commentary or anything else.
+  println!(OUTPUT)
+}
+
+
+#[function_to_string]
+pub fn print_storage_migration(_storage_and_project_description: &str) {
+  /// INPUT: Takes in the STORAGE a website backend uses, its existing MIGRATIONS and a PROJECT_DESCRIPTION
+  /// FUNCTION: Writes one new migration adding the tables, collections or trees the PROJECT_DESCRIPTION needs which the existing MIGRATIONS do not have
+  ///   The migration is written in the format STORAGE describes, so SQL for SQLite and a JSON object for JSON file or sled storage
+  ///   Existing MIGRATIONS are never repeated or changed
+  /// IMPORTANT: Only prints the migration. No commentary or anything else.
+  println!(OUTPUT)
+}
+
+
+#[function_to_string]
+pub fn print_backend_module_plan(_project_description_and_template: &str) {
+  /// INPUT: Takes in a PROJECT_DESCRIPTION and CODE_TEMPLATE for a website backend build
+  /// FUNCTION: Plans how the webserver code is split into Rust modules, each saved as its own file next to main.rs
+  ///   Typical modules are models, db, auth, handlers and routes, but only modules the PROJECT_DESCRIPTION needs are planned
+  ///   main.rs is always written last and is not part of the plan. Modules are listed so each only uses modules listed before it
+  /// FORMAT: Prints a JSON object with a "modules" list. Each module has a snake case "name", a short "purpose" and the
+  ///   "signatures" of every public struct, enum, trait and function other modules may use, written as Rust
+  /// OUTPUT EXAMPLE: {"modules": [{"name": "models", "purpose": "shared types", "signatures": ["pub struct Task { pub id: u64, pub name: String }"]},
+  ///   {"name": "handlers", "purpose": "request handlers", "signatures": ["pub async fn create_task(app_state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder"]}]}
+  /// IMPORTANT: Only prints the JSON object. No commentary or anything else.
+  println!(OUTPUT)
+}
+
+
+#[function_to_string]
+pub fn print_backend_module_code(_module_and_plan: &str) {
+  /// INPUT: Takes in the MODULE to write, the MODULE_PLAN of every module's public signatures, a PROJECT_DESCRIPTION and CODE_TEMPLATE
+  /// FUNCTION: Writes the code for one file of the webserver, following the CODE_TEMPLATE's approach for the purpose in the PROJECT_DESCRIPTION
+  ///   1. Public items match their signatures in MODULE_PLAN exactly, as other modules are written against them
+  ///   2. Items from other modules are used via `use crate::<module>::<item>;`
+  ///   3. When MODULE is main.rs, it declares every module in MODULE_PLAN with `mod <module>;`, registers the routes
+  ///      and binds to the port in the PORT environment variable, falling back to 8080, as shown in the CODE_TEMPLATE
+  /// IMPORTANT: The following libraries are already installed
+  ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, actix_cors, plus any crates listed in EXTRA_CRATES
+  /// OUTPUT: Print ONLY the code for MODULE, nothing else. This function ONLY prints code.
+  println!(OUTPUT)
+}
+
+
 #[function_to_string]
 pub fn print_improved_webserver_code(_project_description_and_template: &str) {
   /// INPUT: Takes in a PROJECT_DESCRIPTION and CODE_TEMPLATE for a website backend build
@@ -21,7 +76,7 @@ pub fn print_improved_webserver_code(_project_description_and_template: &str) {
   ///   2. Makes sure everything requested in the spec from a backend standpoint was followed. If not, add the feature. No code should be implemented later. Everything should be written now.
   ///   3. ONLY writes the code. No commentary.
   /// IMPORTANT: The following libraries are already installed. Does not use ANY libraries other than what was provided in the template
-  ///   reqwest, serde, serde_json, tokio, actix-web, async-trait
+  ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, plus any crates listed in EXTRA_CRATES
   println!(OUTPUT)
 }
 
@@ -36,79 +91,59 @@ pub fn print_fixed_code(_broken_code_with_bugs: &str) {
 
 
 #[function_to_string]
-pub fn print_rest_api_endpoints(_code_input: &str) {
-  /// INPUT: Takes in Rust webserver CODE_INPUT based on actix-web
-  /// FUNCTION: Prints out the JSON schema for url endpoints and their respective types
-  /// LOGIC: Script analyses all code and can categorize into the following object keys:
-  ///   "route": This represents the url path of the endpoint
-  ///   "is_route_dynamic": if a route has curly braces in it such as {symbol} or {id} as an example, then this will be set to true
-  ///   "method": This represents the method being called
-  ///   "request_body": This represents the body of a post method request
-  ///   "response": This represents the output based upon the structs in the code and understanding the functions
-  /// IMPORTANT: Only prints out the JSON schema. No commentary or anything else.
-  /// MUST READ: All keys are strings. Even bool should be wrapped in double quotes as "bool"
-  /// EXAMPLE:
-  /// INPUT_CODE:
-  /// ...
-  /// pub struct Item {
-  ///   pub id: u64,
-  ///   pub name: String,
-  ///   pub completed: bool,
-  /// }
-  /// pub struct User {
-  ///   pub id: u64,
-  ///   pub username: String,
-  ///   pub password: String,
-  /// }
-  /// ...
-  /// HttpServer::new(move || {
-  ///   App::new()
-  ///       .app_data(data.clone())
-  ///       .route("/item", web::post().to(create_item))
-  ///       .route("/item/{id}", web::get().to(read_item))
-  ///       .route("/item/{id}", web::put().to(update_item))
-  ///       .route("/item/{id}", web::delete().to(delete_item))
-  ///       .route("/signup", web::post().to(signup))
-  ///       .route("/crypto", web::get().to(crypto))
-  /// PRINTS JSON FORMATTED OUTPUT:
-  /// [
-  ///   {
-  ///     "route": "/item/{id}",
-  ///     "is_route_dynamic": "true",
-  ///     "method": "get"
-  ///     "request_body": "None",
-  ///     "response": {
-  ///       "id": "number",
-  ///       "name": "string",
-  ///       "completed": "bool",
-  ///     }
-  ///   },
-  ///   {
-  ///     "route": "/item",
-  ///     "is_route_dynamic": "false",
-  ///     "method": "post",
-  ///     "request_body": {
-  ///       "id": "number",
-  ///       "name": "string",
-  ///       "completed": "bool",
-  ///     },
-  ///     "response": "None"
-  ///   },
-  ///   {
-  ///     "route": "/item/{id}",
-  ///     "is_route_dynamic": "true",
-  ///     "method": "delete",
-  ///     "request_body": "None",
-  ///     "response": "None"
-  ///   },
-  ///   {
-  ///     "route": "/crypto",
-  ///     "is_route_dynamic": "false",
-  ///     "method": "get",
-  ///     "request_body": "None",
-  ///     "response": "not_provided"
-  ///   },
-  ///   ... // etc
-  /// ]
+pub fn print_fixed_code_edits(_code_with_bugs: &str) {
+  /// INPUT: Takes in Rust CODE_INPUT and the ERROR_BUGS found
+  /// FUNCTION: Removes bugs from code by printing the smallest set of search/replace edit blocks needed, leaving everything else as it is
+  /// FORMAT: Each edit block is printed as follows, where the SEARCH lines are copied exactly from CODE_INPUT and match only one place in it
+  ///   <<<<<<< SEARCH
+  ///   lines to find
+  ///   =======
+  ///   lines to replace them with
+  ///   >>>>>>> REPLACE
+  /// IMPORTANT: Only prints out edit blocks. No commentary or anything else
+  println!(OUTPUT)
+}
+
+
+#[function_to_string]
+pub fn print_improved_webserver_code_edits(_project_description_and_code: &str) {
+  /// INPUT: Takes in a PROJECT_DESCRIPTION and the current CODE_INPUT for a website backend build
+  /// FUNCTION: Removes any bugs and adds anything in the spec from a backend standpoint which is missing, by printing search/replace edit blocks
+  /// FORMAT: Each edit block is printed as follows, where the SEARCH lines are copied exactly from CODE_INPUT and match only one place in it
+  ///   <<<<<<< SEARCH
+  ///   lines to find
+  ///   =======
+  ///   lines to replace them with
+  ///   >>>>>>> REPLACE
+  /// IMPORTANT: The following libraries are already installed. Does not use ANY libraries other than what is already used in CODE_INPUT
+  ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, plus any crates listed in EXTRA_CRATES
+  /// IMPORTANT: Only prints out edit blocks. No commentary or anything else
+  println!(OUTPUT)
+}
+
+
+#[function_to_string]
+pub fn print_backend_integration_tests(_api_endpoints_and_code: &str) {
+  /// INPUT: Takes in API_ENDPOINTS_JSON_SCHEMA listing every route of a Rust actix-web webserver, and the webserver CODE_INPUT
+  /// FUNCTION: Writes integration tests for every route in API_ENDPOINTS_JSON_SCHEMA using #[actix_web::test] with actix_web::test::{init_service, call_service, read_body_json, TestRequest}
+  /// IMPORTANT: The tests are saved as a child module of CODE_INPUT, so they start with `use super::*;` to reach its handlers, structs and app state
+  ///   1. Each test builds the App with the same app_data and routes as CODE_INPUT, using fresh state
+  ///   2. Resources are created with POST before they are read, updated or deleted
+  ///   3. Tests check both status codes and response bodies against the schema
+  ///   4. Routes with "is_protected": true are called once without an Authorization header, checking they are rejected with 401,
+  ///      and otherwise with the token from registering and logging in sent as `Authorization: Bearer <token>`
+  ///   5. Only uses actix-web, serde and serde_json. Does not wrap the tests in a `mod` block
+  /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
+  println!(OUTPUT)
+}
+
+
+#[function_to_string]
+pub fn print_rest_api_endpoint_descriptions(_routes_and_code: &str) {
+  /// INPUT: Takes in a list of ROUTES already extracted from Rust webserver CODE_INPUT based on actix-web
+  /// FUNCTION: Prints a short plain English description of what each route does, based on its handler in CODE_INPUT
+  /// FORMAT: Prints a JSON list of strings with exactly one description per route, in the same order as ROUTES
+  /// OUTPUT EXAMPLE: ["Creates a new item", "Lists all items", "Fetches a single item by id"]
+  /// IMPORTANT: Only prints the JSON list of strings. No commentary or anything else.
   println!(OUTPUT)
 }
@@ -19,7 +19,7 @@ pub fn print_code_bugs_resolution(_existing_code_and_error: &str) {
 #[function_to_string]
 pub fn print_recommended_site_pages(_project_description_and_backend_code_logic: &str) {
   /// INPUT: Takes in a PROJECT_DESCRIPTION and CODE_LOGIC for a websites backend. This function interprets a page structure solution for the frontend
-  /// FUNCTION: Outputs up to 2 recommended pages for an SPA application that would BEST suit the PROJECT_DESCRIPTION and CODE_LOGIC
+  /// FUNCTION: Outputs as many recommended pages as the site needs, usually 2 to 5, for an SPA application that would BEST suit the PROJECT_DESCRIPTION and CODE_LOGIC
   /// IMPORTANT: 
   ///   1. The "suggested_content_sections" do not mention headers or footers as these are already covered
   ///   2. The keys within the "suggested_content_sections" object can vary based on the functions imagination
@@ -68,7 +68,7 @@ pub fn print_recommended_site_pages(_project_description_and_backend_code_logic:
 // Page Architecture
 #[function_to_string]
 pub fn print_recommended_site_pages_with_apis(_website_specification: &str) {
-  /// INPUT: Takes in a PROJECT_DESCRIPTION, and WEBSITE_SPECIFICATION with API_ENDPOINTS and PAGES for a websites frontend build.
+  /// INPUT: Takes in a PROJECT_DESCRIPTION, and WEBSITE_SPECIFICATION with PAGES, EXTERNAL_API_ROUTES and INTERNAL_API_ROUTES as an OpenAPI document for a websites frontend build.
   /// FUNCTION: Assignes endpoints to each page
   /// OUTPUT FORMAT: The function outputs in a JSON format as follows:
   ///   [
@@ -163,7 +163,7 @@ pub fn print_completed_logo_with_brand_name_react_component(_website_specificati
   ///   )
   /// }
   /// export default Logo
-  /// IMPORTANT: Tailwind CSS is used for styling. Does NOT use any extrernal libraries not included in this list: [axios, @mui/icons-material", react, tailwind]
+  /// IMPORTANT: Tailwind CSS is used for styling. Does NOT use any extrernal libraries not included in this list: [axios, @mui/icons-material", react, react-router-dom, tailwind]
   /// REMEMBER: This function only prints React Typescript component code. Nothing else. Just the code and WITHOUT any backticks at the start of the file ```.
   println!(OUTPUT)
 }
@@ -175,28 +175,25 @@ pub fn print_header_navigation_react_component(_website_specification: &str) {
   /// INPUT: Takes in a WEBSITE_SPECIFICATION for a websites frontend.
   /// FUNCTION: Writes the code for a REACT TYPESCRIPT navigation header bar for a frontend website
   /// OUTPOUT: The navigation header bar includes the following
-  ///   1 - The existing logo which shows as <Logo />, the logo should have an 'import Logo from "./Logo"' at the top of the component
-  ///   2 - Page links based on the pages provided in the specification
-  ///   3 - This should be responsive based upon the size of the screen using tailwind css. A small screen should have a burger menu with slider
-  ///   4 - Takes in a getter and setter prop called currentPage and setCurrentPage respectively
-  ///   5 - Depending on the page, a different color will show on the navigation links
+  ///   1 - Page links for every entry of PAGE_LINKS, an array of { path, label } imported from "../../routes", each rendered as a react-router-dom NavLink
+  ///   2 - This should be responsive based upon the size of the screen using tailwind css. A small screen should have a burger menu with slider
+  ///   3 - Takes in no props, the current page comes from the router
+  ///   4 - Depending on the page, a different color will show on the navigation links using the isActive flag NavLink passes to className
+  ///   5 - Does NOT render the logo, the app shell already shows <Logo /> next to this component in the header
   /// TEMPLATE:
-  /// import Logo from "./Logo"
-  /// type Props = {
-  ///   currentPage: string;
-  ///   setCurrentPage: any; // Leave this as any as an unknown setter function will be passed here
-  /// }
-  /// function Navigation({setCurrentPage, currentPage}: Props) {
+  /// import { NavLink } from "react-router-dom"
+  /// import { PAGE_LINKS } from "../../routes"
+  /// function Navigation() {
   ///   return (
-  ///     <div>
+  ///     <nav>
   ///       YOUR COMPONENT CODE GOES HERE
-  ///     </div>
+  ///     </nav>
   ///   )
   /// }
   /// export default Navigation
   /// DO NOT LEAVE ANY CODE UNFINISHED FOR LATER. CODE EVERYTHING INCLUDING THE SLIDER MENU NOW.
   /// IMPORTANT: This function only prints a full react component with completed typescript code, nothing else. Just the code and WITHOUT any backticks at the start of the file ```.
-  /// IMPORTANT: Tailwind CSS is used for styling. Does NOT use any extrernal libraries not included in this list: [axios, @mui/icons-material", react, tailwind]
+  /// IMPORTANT: Tailwind CSS is used for styling. Does NOT use any extrernal libraries not included in this list: [axios, @mui/icons-material", react, react-router-dom, tailwind]
   println!(OUTPUT)
 }
 
@@ -207,16 +204,14 @@ pub fn print_footer_navigation_react_component(_website_specification: &str) {
   /// INPUT: Takes in a WEBSITE_SPECIFICATION for a websites frontend.
   /// FUNCTION: Writes only the HTML code for a REACT TYPESCRIPT footer for website
   /// OUTPOUT: The navigation footer bar includes the following
-  ///   1 - Page links based on the pages provided in the specification
+  ///   1 - Page links for every entry of PAGE_LINKS, an array of { path, label } imported from "../../routes", each rendered as a react-router-dom NavLink
   ///   2 - Must be responsive and be a small fixed bar to the bottom of the screen if in mobile view
-  ///   3 - Takes in a getter and setter prop called currentPage and setCurrentPage respectively
-  ///   4 - Depending on the page, a different color will show on the navigation links
+  ///   3 - Takes in no props, the current page comes from the router
+  ///   4 - Depending on the page, a different color will show on the navigation links using the isActive flag NavLink passes to className
   /// TEMPLATE:
-  /// type Props = {
-  ///   currentPage: string;
-  ///   setCurrentPage: any; // Leave this as any as an unknown setter function will be passed here
-  /// }
-  /// function Footer({setCurrentPage, currentPage}: Props) {
+  /// import { NavLink } from "react-router-dom"
+  /// import { PAGE_LINKS } from "../../routes"
+  /// function Footer() {
   ///   return (
   ///     <div>
   ///       YOUR COMPONENT CODE GOES HERE
@@ -225,27 +220,7 @@ pub fn print_footer_navigation_react_component(_website_specification: &str) {
   /// }
   /// export default Footer
   /// IMPORTANT: This function only prints a full react component with completed typescript code, nothing else. Just the code and WITHOUT any backticks at the start of the file ```.
-  /// IMPORTANT: Tailwind CSS is used for styling. Does NOT use any extrernal libraries not included in this list: [axios, @mui/icons-material", react, tailwind]
-  println!(OUTPUT)
-}
-
-
-// Integration - React Hooks
-#[function_to_string]
-pub fn print_react_typescript_hook_component(_api_endpoints: &str) {
-  /// INPUT: Takes in a list of API_ENDPOINTS_JSON_SCHEMA and with their request and response schema. All these endpoints are called from http://localhost:8080
-  /// OUTPUT: A full REACT "useCall" TYPESCRIPT CUSTOM REACT HOOK component connecting to and returning data for ALL of the endpoints. No endpoints are left out
-  /// NOTE: All code is fully written and interfaces made available for decoding any returned data
-  /// COMPONENT TITLE: The components title is "useCall"
-  /// API BASE ROUTE: endpoints are called from http://localhost:8080
-  /// IMPORTANT: The component is fully working with typescript annotations types declared for everything or //@ts-ignore if unsure
-  /// IMPORTANT: Does NOT use any extrernal libraries not included in this list: [axios, @mui/icons-material", react, tailwind]
-  /// FORMAT: Just prints the react typescript component, Nothing else. . Just the code and WITHOUT any backticks at the start of the file ```.
-  /// ERROR HANDLING: All error handling includes "as any" to prevent build errors on type issues:
-  ///  catch (e) {
-  ///   setError(e as any);
-  ///   ...
-  ///  }
+  /// IMPORTANT: Tailwind CSS is used for styling. Does NOT use any extrernal libraries not included in this list: [axios, @mui/icons-material", react, react-router-dom, tailwind]
   println!(OUTPUT)
 }
 
@@ -269,15 +244,16 @@ pub fn print_html_webpage_content_with_text(_page_content_spec: &str) {
 
 // Integration - Create Component Template - API Integration
 #[function_to_string]
-pub fn print_create_react_component_with_API_integration(_page_specification: &str) {
-  /// INPUT: Receives API_SPECIFICATION information with API_ROUTES and API_HOOK relevant to page if any
+pub fn print_create_react_component_with_api_integration(_page_specification: &str) {
+  /// INPUT: Receives API_SPECIFICATION information with API_ROUTES relevant to page if any and the typed API_CLIENT calling every route
   /// OUTPUT: Converts the input into a full REACT TYPESCRIPT based component including handling the required API requests
   /// and presenting the data in the component render section.
   /// RULES: 
   ///   1. Does not leave anything unfinished, writes ALL the code required to convert the Html into a fully working React Typescript component
-  ///   2. useCall hook file is saved in the following directory for import "../../hooks/useCall". So import useCall from "../../hooks/useCall"
+  ///   2. The API_CLIENT is saved in the following directory for import "../../api/client". Imports its functions and types from there, such as import { getTask, type Task } from "../../api/client". Never calls axios directly or redeclares the types
   ///   3. ALL relevant API Routes will be used as part of this component
-  ///   4. Does NOT use any extrernal libraries that are not included in this list: [axios, @mui/icons-material", react, tailwind]
+  ///   4. Does NOT use any extrernal libraries that are not included in this list: [axios, @mui/icons-material", react, react-router-dom, tailwind]
+  ///   5. The component is named COMPONENT_NAME and is the default export
   /// ERROR HANDLING: All error handling includes "as any" to prevent build errors on type issues:
   ///  catch (e as any) { ...
   /// TEMPLATE:
@@ -299,11 +275,11 @@ pub fn print_create_full_react_component(_page_content_spec: &str) {
   /// INPUT: Receives HTML_CONTENT_WIREFRAME and REACT_TYPESCRYPT_COMPONENT code
   /// OUTPUT: Combines ALL HTML_CONTENT and ALL REACT_TYPESCRYPT_COMPONENT into one MasterPage Component
   /// RULES: 
-  ///   1. Does NOT use any extrernal libraries that are not included in this list: [axios, @mui/icons-material", react, tailwind]
+  ///   1. Does NOT use any extrernal libraries that are not included in this list: [axios, @mui/icons-material", react, react-router-dom, tailwind]
   ///   2. Ensures that ALL code will work as a React Typescript component
   ///   3. Adds basic tailwind css styling and @mui/icons-material icons where relevant
   /// TEMPLATE:
-  /// import useCall from "../../hooks/useCall"
+  /// import { getTask, type Task } from "../../api/client"
   /// function MasterPage() {
   ///   return (
   ///     <div className="w-full">
@@ -324,7 +300,7 @@ pub fn print_give_component_fantastic_styling(_page_specification: &str) {
   /// RULES: 
   ///   1. Significantly improves styling with Tailwind and if relevant @mui/icons-material"
   ///   2. Leaves nothing to do later or unfinished in the code. This is a polished component. Everything must be great.
-  ///   3. Does NOT use any extrernal libraries that are not included in this list: [axios, @mui/icons-material", react, tailwind]
+  ///   3. Does NOT use any extrernal libraries that are not included in this list: [axios, @mui/icons-material", react, react-router-dom, tailwind]
   /// ERROR HANDLING: All error handling includes "as any" to prevent build errors on type issues
   /// OUTPUT: Just prints the code for the full component. Nothing else. No ``` etc. Just the component code.
   println!(OUTPUT)
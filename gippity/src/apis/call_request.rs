@@ -1,13 +1,59 @@
 use crate::models::general::llm::{ChatCompletion, APIResponse, Message};
 use reqwest::Client;
 use std::env;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU32, Ordering};
 use dotenv::dotenv;
 
 
+// Model and call budget shared by every agent for the run
+#[derive(Debug, Clone)]
+pub struct LlmSettings {
+    pub model: String,
+    pub budget: Option<u32>,
+}
+
+static LLM_SETTINGS: OnceLock<LlmSettings> = OnceLock::new();
+static LLM_CALLS_MADE: AtomicU32 = AtomicU32::new(0);
+
+
+// Set model and budget for the run (falls back to OPEN_AI_MODEL or gpt-4)
+pub fn configure_llm(model: Option<String>, budget: Option<u32>) -> &'static LlmSettings {
+    dotenv().ok();
+    LLM_SETTINGS.get_or_init(|| LlmSettings {
+        model: model.unwrap_or_else(|| env::var("OPEN_AI_MODEL").unwrap_or("gpt-4".to_string())),
+        budget
+    })
+}
+
+
+// Number of calls made to the Large Language Model so far, including those of earlier sessions of the run
+pub fn llm_calls_made() -> u32 {
+    LLM_CALLS_MADE.load(Ordering::SeqCst)
+}
+
+
+// Carry on counting from the calls a resumed run already made, so its budget covers the whole run
+pub fn restore_llm_calls(calls_made: u32) {
+    LLM_CALLS_MADE.store(calls_made, Ordering::SeqCst);
+}
+
+
 // Call Large Language Model (i.e. GPT-4)
 pub async fn call_gpt(messages: Vec<Message>) -> Result<String, Box<dyn std::error::Error + Send>> {
     dotenv().ok();
 
+    // Guard: Ensure call budget not used up
+    let settings: &LlmSettings = configure_llm(None, None);
+    let within_budget: Result<u32, u32> = LLM_CALLS_MADE.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |calls_made| match settings.budget {
+        Some(budget) if calls_made >= budget => None,
+        _ => Some(calls_made + 1)
+    });
+    if within_budget.is_err() {
+        let err_msg: String = format!("LLM call budget of {} used up", settings.budget.unwrap_or_default());
+        return Err(Box::new(std::io::Error::other(err_msg)));
+    }
+
     // Extract API Key information
     let api_key: String = env::var("OPEN_AI_KEY").expect("OPEN_AI_KEY must be set");
     let api_org: String = env::var("OPEN_AI_ORG").expect("OPEN_AI_ORG must be set");
@@ -30,8 +76,8 @@ pub async fn call_gpt(messages: Vec<Message>) -> Result<String, Box<dyn std::err
 
     // Structure input chat
     let chat_completion: ChatCompletion = ChatCompletion {
-        model: "gpt-4".to_string(),
-        messages: messages
+        model: settings.model.clone(),
+        messages
     };
     
     // // Troubleshooting: Show raw response if issue
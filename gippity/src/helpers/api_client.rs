@@ -0,0 +1,312 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+
+// Where the generated client and its types are saved in the frontend
+pub const API_CLIENT_PATH: &str = "/src/api/client.ts";
+pub const API_TYPES_PATH: &str = "/src/api/types.ts";
+
+// First line of every generated file
+const GENERATED_HEADER: &str = "// Generated from the backend's OpenAPI document. Do not edit, it is regenerated with the backend.\n";
+
+// Where the backend is served from, unless VITE_API_BASE_URL is set when the frontend is built
+const DEFAULT_API_BASE_URL: &str = "http://localhost:8080";
+
+// Methods whose axios call takes a request body
+const BODY_METHODS: [&str; 3] = ["post", "put", "patch"];
+
+
+// Typed TypeScript client for the backend, along with the interfaces it uses
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiClient {
+  pub client: String,
+  pub types: String,
+}
+
+
+// Words of a name such as "user_id" or "fitness-progress", as PascalCase
+fn pascal_case(name: &str) -> String {
+  name.split(|c: char| !c.is_ascii_alphanumeric())
+    .filter(|word| !word.is_empty())
+    .map(|word| {
+      let mut chars = word.chars();
+      chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+    })
+    .collect()
+}
+
+
+// Words of a name as a camelCase TypeScript identifier
+fn camel_case(name: &str) -> String {
+  let pascal: String = pascal_case(name);
+  let mut chars = pascal.chars();
+  let camel: String = chars.next().map(|first| first.to_ascii_lowercase().to_string() + chars.as_str()).unwrap_or_default();
+  match camel.chars().next() {
+    Some(first) if first.is_ascii_alphabetic() => camel,
+    _ => format!("_{}", camel)
+  }
+}
+
+
+// Property name, quoted when it is not a plain identifier
+fn property_name(name: &str) -> String {
+  let is_identifier: bool = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+  match is_identifier {
+    true => name.to_string(),
+    false => format!("{:?}", name)
+  }
+}
+
+
+// Component name a schema refers to
+fn ref_name(schema: &Value) -> Option<&str> {
+  schema.get("$ref")?.as_str()?.rsplit('/').next()
+}
+
+
+// TypeScript type of a JSON schema, noting any component types it uses
+fn ts_type(schema: &Value, used: &mut BTreeSet<String>) -> String {
+  if let Some(name) = ref_name(schema) {
+    let type_name: String = pascal_case(name);
+    used.insert(type_name.clone());
+    return type_name;
+  }
+  match schema.get("type").and_then(Value::as_str) {
+    Some("number") | Some("integer") => "number".to_string(),
+    Some("string") => "string".to_string(),
+    Some("boolean") => "boolean".to_string(),
+    Some("array") => match schema.get("items") {
+      Some(items) => format!("{}[]", ts_type(items, used)),
+      None => "unknown[]".to_string()
+    },
+    Some("object") => match schema.get("properties").and_then(Value::as_object) {
+      Some(properties) => {
+        let fields: Vec<String> = object_fields(schema, properties, used);
+        format!("{{ {} }}", fields.join("; "))
+      },
+      None => "Record<string, unknown>".to_string()
+    },
+    _ => "unknown".to_string()
+  }
+}
+
+
+// Fields of an object schema, optional unless required
+fn object_fields(schema: &Value, properties: &Map<String, Value>, used: &mut BTreeSet<String>) -> Vec<String> {
+  let required: Vec<&str> = schema.get("required").and_then(Value::as_array)
+    .map(|required| required.iter().filter_map(Value::as_str).collect())
+    .unwrap_or_default();
+  properties.iter().map(|(name, property)| {
+    let optional: &str = if required.contains(&name.as_str()) { "" } else { "?" };
+    format!("{}{}: {}", property_name(name), optional, ts_type(property, used))
+  }).collect()
+}
+
+
+// Interface definitions for every component schema
+fn render_types(components: &Map<String, Value>) -> String {
+  let mut types: String = GENERATED_HEADER.to_string();
+  for (name, schema) in components {
+    let mut used: BTreeSet<String> = BTreeSet::new();
+    types.push('\n');
+    match schema.get("properties").and_then(Value::as_object) {
+      Some(properties) => {
+        types.push_str(&format!("export interface {} {{\n", pascal_case(name)));
+        for field in object_fields(schema, properties, &mut used) {
+          types.push_str(&format!("  {};\n", field));
+        }
+        types.push_str("}\n");
+      },
+      None => types.push_str(&format!("export type {} = {};\n", pascal_case(name), ts_type(schema, &mut used)))
+    }
+  }
+  types
+}
+
+
+// Function name for a route, such as getTaskById for GET /task/{id}
+fn function_name(method: &str, route: &str) -> String {
+  let words: String = route.split('/')
+    .filter(|segment| !segment.is_empty())
+    .map(|segment| match segment.strip_prefix('{').and_then(|param| param.strip_suffix('}')) {
+      Some(param) => format!("By{}", pascal_case(param)),
+      None => pascal_case(segment)
+    })
+    .collect();
+  match words.is_empty() {
+    true => format!("{}Root", method),
+    false => format!("{}{}", method, words)
+  }
+}
+
+
+// Typed function calling one route
+fn render_function(name: &str, route: &str, method: &str, operation: &Value, used: &mut BTreeSet<String>) -> String {
+
+  // Arguments: path parameters in order, then the request body
+  let mut args: Vec<String> = vec![];
+  let mut url: String = String::new();
+  for segment in route.split('/').filter(|segment| !segment.is_empty()) {
+    url.push('/');
+    match segment.strip_prefix('{').and_then(|param| param.strip_suffix('}')) {
+      Some(param) => {
+        let schema: Option<&Value> = operation.get("parameters").and_then(Value::as_array)
+          .and_then(|parameters| parameters.iter().find(|parameter| parameter.get("name").and_then(Value::as_str) == Some(param)))
+          .and_then(|parameter| parameter.get("schema"));
+        let arg: String = camel_case(param);
+        args.push(format!("{}: {}", arg, schema.map(|schema| ts_type(schema, used)).unwrap_or("string".to_string())));
+        url.push_str(&format!("${{encodeURIComponent(String({}))}}", arg));
+      },
+      None => url.push_str(segment)
+    }
+  }
+  if url.is_empty() {
+    url.push('/');
+  }
+  let body: Option<&Value> = operation.pointer("/requestBody/content/application~1json/schema");
+  if let Some(schema) = body {
+    args.push(format!("body: {}", ts_type(schema, used)));
+  }
+
+  // Response type, or void when the route returns no body
+  let response: Option<String> = operation.pointer("/responses/200/content/application~1json/schema")
+    .map(|schema| ts_type(schema, used));
+
+  // Call, sending the login token to protected routes
+  let is_protected: bool = operation.get("security").and_then(Value::as_array).is_some_and(|security| !security.is_empty());
+  let mut call_args: Vec<String> = vec![format!("`${{API_BASE_URL}}{}`", url)];
+  if BODY_METHODS.contains(&method) {
+    call_args.push(if body.is_some() { "body" } else { "undefined" }.to_string());
+  }
+  if is_protected {
+    call_args.push("{ headers: authHeaders() }".to_string());
+  }
+
+  let mut function: String = String::new();
+  if let Some(summary) = operation.get("summary").and_then(Value::as_str) {
+    function.push_str(&format!("// {}\n", summary.replace('\n', " ")));
+  }
+  match response {
+    Some(response_type) => {
+      function.push_str(&format!("export const {} = async ({}): Promise<{}> => {{\n", name, args.join(", "), response_type));
+      function.push_str(&format!("  const response = await axios.{}<{}>({});\n", method, response_type, call_args.join(", ")));
+      function.push_str("  return response.data;\n");
+    },
+    None => {
+      function.push_str(&format!("export const {} = async ({}): Promise<void> => {{\n", name, args.join(", ")));
+      function.push_str(&format!("  await axios.{}({});\n", method, call_args.join(", ")));
+    }
+  }
+  function.push_str("};\n");
+  function
+}
+
+
+// Generates the typed client and its interfaces from an OpenAPI document
+// Each route becomes a function named after its method and path, such as getTaskById
+pub fn generate_api_client(document: &Value) -> ApiClient {
+  let empty: Map<String, Value> = Map::new();
+  let components: &Map<String, Value> = document.pointer("/components/schemas").and_then(Value::as_object).unwrap_or(&empty);
+  let paths: &Map<String, Value> = document.get("paths").and_then(Value::as_object).unwrap_or(&empty);
+
+  // One function per route, keeping names unique
+  let mut used: BTreeSet<String> = BTreeSet::new();
+  let mut names: BTreeSet<String> = BTreeSet::new();
+  let mut functions: Vec<String> = vec![];
+  let mut is_auth_needed: bool = false;
+  for (route, path_item) in paths {
+    for (method, operation) in path_item.as_object().unwrap_or(&empty) {
+      let base_name: String = function_name(method, route);
+      let mut name: String = base_name.clone();
+      let mut suffix: u32 = 2;
+      while names.contains(&name) {
+        name = format!("{}{}", base_name, suffix);
+        suffix += 1;
+      }
+      names.insert(name.clone());
+      is_auth_needed |= operation.get("security").is_some();
+      functions.push(render_function(&name, route, method, operation, &mut used));
+    }
+  }
+
+  // Imports and shared setup, re-exporting types so pages only import from the client
+  let mut client: String = GENERATED_HEADER.to_string();
+  if !functions.is_empty() {
+    client.push_str("import axios from \"axios\";\n");
+  }
+  if !used.is_empty() {
+    let type_names: String = used.iter().cloned().collect::<Vec<String>>().join(", ");
+    client.push_str(&format!("import type {{ {} }} from \"./types\";\n", type_names));
+  }
+  if !components.is_empty() {
+    let type_names: String = components.keys().map(|name| pascal_case(name)).collect::<Vec<String>>().join(", ");
+    client.push_str(&format!("export type {{ {} }} from \"./types\";\n", type_names));
+  }
+  client.push_str(&format!("\nexport const API_BASE_URL = import.meta.env.VITE_API_BASE_URL ?? \"{}\";\n", DEFAULT_API_BASE_URL));
+  if is_auth_needed {
+    client.push_str(concat!(
+      "\nlet authToken: string | null = null;\n",
+      "\n// Sets the token from logging in, which is sent to protected routes until it is cleared with null\n",
+      "export const setAuthToken = (token: string | null): void => {\n",
+      "  authToken = token;\n",
+      "};\n",
+      "\nconst authHeaders = (): Record<string, string> => (authToken ? { Authorization: `Bearer ${authToken}` } : {});\n",
+    ));
+  }
+  for function in functions {
+    client.push('\n');
+    client.push_str(&function);
+  }
+
+  ApiClient { client, types: render_types(components) }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::helpers::openapi::openapi_document;
+  use crate::helpers::routes::extract_routes;
+  use crate::models::agents::agent_traits::RouteObject;
+
+  #[test]
+  fn generates_client_from_code_template() {
+    let code: &str = include_str!("../template/project/backend/src/codetemplate.rs");
+    let mut routes: Vec<RouteObject> = extract_routes(code).unwrap();
+    routes[1].description = Some("Lists every task".to_string());
+    routes[1].is_protected = true;
+    let api_client: ApiClient = generate_api_client(&openapi_document("Todo app", &routes));
+
+    assert!(api_client.types.contains("export interface Task {\n  completed: boolean;\n  id: number;\n  name: string;\n}\n"));
+    assert!(api_client.types.contains("export interface User {\n"));
+    assert!(api_client.client.contains("import type { Task, User } from \"./types\";\n"));
+    assert!(api_client.client.contains("export const API_BASE_URL = import.meta.env.VITE_API_BASE_URL ?? \"http://localhost:8080\";\n"));
+    assert!(api_client.client.contains(concat!(
+      "// Lists every task\n",
+      "export const getTask = async (): Promise<Task[]> => {\n",
+      "  const response = await axios.get<Task[]>(`${API_BASE_URL}/task`, { headers: authHeaders() });\n",
+      "  return response.data;\n",
+      "};\n",
+    )));
+    assert!(api_client.client.contains(concat!(
+      "export const putTaskById = async (id: string, body: Task): Promise<void> => {\n",
+      "  await axios.put(`${API_BASE_URL}/task/${encodeURIComponent(String(id))}`, body);\n",
+    )));
+    assert!(api_client.client.contains("export const deleteTaskById = async (id: number): Promise<void> => {\n"));
+    assert!(api_client.client.contains("export const setAuthToken"));
+  }
+
+  #[test]
+  fn matches_scaffolded_client() {
+    let empty: Value = openapi_document("", &[]);
+    let api_client: ApiClient = generate_api_client(&empty);
+    assert_eq!(api_client.client, include_str!("../template/project/frontend/src/api/client.ts"));
+    assert_eq!(api_client.types, include_str!("../template/project/frontend/src/api/types.ts"));
+    assert_eq!(function_name("get", "/"), "getRoot");
+    assert_eq!(function_name("post", "/fitness-progress/{user_id}/log"), "postFitnessProgressByUserIdLog");
+    assert_eq!(camel_case("2fa"), "_2fa");
+    assert_eq!(property_name("user-id"), "\"user-id\"");
+  }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+
+// Where the app shell is saved in the frontend
+pub const CONTROLLER_PATH: &str = "/src/components/Controller.tsx";
+pub const PAGE_LINKS_PATH: &str = "/src/routes.ts";
+pub const NOT_FOUND_PATH: &str = "/src/components/pages/NotFound.tsx";
+
+// First line of every generated file
+const GENERATED_HEADER: &str = "// Generated from the site's pages. Do not edit, it is regenerated with the frontend.\n";
+
+// Components of the app shell which page components must not share a name with
+const RESERVED_COMPONENT_NAMES: [&str; 5] = ["Controller", "Logo", "Navigation", "Footer", "NotFound"];
+
+// Page shown for any route which is not one of the site's pages
+const NOT_FOUND_PAGE: &str = r#"import { Link } from "react-router-dom";
+import Logo from "../shared/Logo";
+
+function NotFound() {
+  return (
+    <main className="flex flex-col items-center justify-center gap-4 px-4 py-16 text-center">
+      <Logo />
+      <h1 className="text-3xl font-bold">Page not found</h1>
+      <p className="text-gray-600">The page you are looking for does not exist.</p>
+      <Link to="/" className="text-blue-500 underline">
+        Back to the home page
+      </Link>
+    </main>
+  );
+}
+
+export default NotFound;
+"#;
+
+
+// Route of one page in the app shell
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageRoute {
+  pub page_name: String,
+  pub component_name: String,
+  pub path: String,
+  pub label: String,
+}
+
+
+// Router, page links and 404 page wiring every page together
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppShell {
+  pub controller: String,
+  pub page_links: String,
+  pub not_found: String,
+}
+
+
+// Words of a page name such as "home_page" or "Progress Dashboard"
+fn page_words(page: &str) -> Vec<String> {
+  page.split(|c: char| !c.is_ascii_alphanumeric())
+    .filter(|word| !word.is_empty())
+    .map(|word| word.to_ascii_lowercase())
+    .collect()
+}
+
+
+// Word with its first letter capitalised
+fn capitalise(word: &str) -> String {
+  let mut chars = word.chars();
+  chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+}
+
+
+// First of a name, then the name with a number appended, which is not yet taken
+fn unique_name(base_name: String, separator: &str, taken: &mut HashSet<String>) -> String {
+  let mut name: String = base_name.clone();
+  let mut suffix: u32 = 2;
+  while taken.contains(&name) {
+    name = format!("{}{}{}", base_name, separator, suffix);
+    suffix += 1;
+  }
+  taken.insert(name.clone());
+  name
+}
+
+
+// React component name for each page, such as HomePage for "home_page", kept unique
+pub fn page_component_names(pages: &[String]) -> Vec<String> {
+  let mut taken: HashSet<String> = RESERVED_COMPONENT_NAMES.iter().map(|name| name.to_string()).collect();
+  pages.iter().map(|page| {
+    let pascal: String = page_words(page).iter().map(|word| capitalise(word)).collect();
+    let base_name: String = match pascal.chars().next() {
+      Some(first) if first.is_ascii_alphabetic() => pascal,
+      _ => format!("Page{}", pascal)
+    };
+    unique_name(base_name, "", &mut taken)
+  }).collect()
+}
+
+
+// Route for each page, the first page being the home page at "/"
+pub fn page_routes(pages: &[String]) -> Vec<PageRoute> {
+  let mut taken: HashSet<String> = HashSet::from(["/".to_string()]);
+  pages.iter().zip(page_component_names(pages)).enumerate().map(|(index, (page, component_name))| {
+    let words: Vec<String> = page_words(page);
+    let path: String = match index {
+      0 => "/".to_string(),
+      _ => unique_name(format!("/{}", words.join("-")), "-", &mut taken)
+    };
+    let label: String = match words.is_empty() {
+      true => component_name.clone(),
+      false => words.iter().map(|word| capitalise(word)).collect::<Vec<String>>().join(" ")
+    };
+    PageRoute { page_name: page.clone(), component_name, path, label }
+  }).collect()
+}
+
+
+// Generates the app shell routing to every page, with a 404 page for anything else
+pub fn generate_app_shell(pages: &[String]) -> AppShell {
+  let routes: Vec<PageRoute> = page_routes(pages);
+
+  // Links read by the navigation and footer
+  let mut page_links: String = GENERATED_HEADER.to_string();
+  page_links.push_str("export type PageLink = {\n  path: string;\n  label: string;\n};\n\n");
+  page_links.push_str("export const PAGE_LINKS: PageLink[] = [\n");
+  for route in &routes {
+    page_links.push_str(&format!("  {{ path: {:?}, label: {:?} }},\n", route.path, route.label));
+  }
+  page_links.push_str("];\n");
+
+  // Router with a route per page, under a header with the logo and navigation
+  let mut controller: String = GENERATED_HEADER.to_string();
+  controller.push_str("import { BrowserRouter, Route, Routes } from \"react-router-dom\";\n");
+  controller.push_str("import Logo from \"./shared/Logo\";\n");
+  controller.push_str("import Navigation from \"./shared/Navigation\";\n");
+  controller.push_str("import Footer from \"./shared/Footer\";\n");
+  for route in &routes {
+    controller.push_str(&format!("import {} from \"./pages/{}\";\n", route.component_name, route.component_name));
+  }
+  controller.push_str("import NotFound from \"./pages/NotFound\";\n\n");
+  controller.push_str("function Controller() {\n  return (\n    <BrowserRouter>\n");
+  controller.push_str("      <header className=\"flex justify-between items-center px-4 py-2 shadow-md\">\n        <Logo />\n        <Navigation />\n      </header>\n");
+  controller.push_str("      <Routes>\n");
+  for route in &routes {
+    controller.push_str(&format!("        <Route path=\"{}\" element={{<{} />}} />\n", route.path, route.component_name));
+  }
+  controller.push_str("        <Route path=\"*\" element={<NotFound />} />\n");
+  controller.push_str("      </Routes>\n      <Footer />\n    </BrowserRouter>\n  );\n}\n\nexport default Controller;\n");
+
+  AppShell { controller, page_links, not_found: format!("{}{}", GENERATED_HEADER, NOT_FOUND_PAGE) }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn routes_every_page() {
+    let pages: Vec<String> = vec!["home_page", "progress-dashboard", "Home Page", "404", "not_found"]
+      .into_iter().map(String::from).collect();
+    let routes: Vec<PageRoute> = page_routes(&pages);
+    let names: Vec<&str> = routes.iter().map(|route| route.component_name.as_str()).collect();
+    let paths: Vec<&str> = routes.iter().map(|route| route.path.as_str()).collect();
+    assert_eq!(names, vec!["HomePage", "ProgressDashboard", "HomePage2", "Page404", "NotFound2"]);
+    assert_eq!(paths, vec!["/", "/progress-dashboard", "/home-page", "/404", "/not-found"]);
+    assert_eq!(routes[1].label, "Progress Dashboard");
+
+    let app_shell: AppShell = generate_app_shell(&pages);
+    assert!(app_shell.controller.contains("import HomePage2 from \"./pages/HomePage2\";\n"));
+    assert!(app_shell.controller.contains("import Logo from \"./shared/Logo\";\n"));
+    assert!(app_shell.controller.contains("        <Logo />\n        <Navigation />\n"));
+    assert!(app_shell.controller.contains("        <Route path=\"/progress-dashboard\" element={<ProgressDashboard />} />\n"));
+    assert!(app_shell.controller.contains("        <Route path=\"*\" element={<NotFound />} />\n"));
+    assert!(app_shell.page_links.contains("  { path: \"/404\", label: \"404\" },\n"));
+  }
+
+  #[test]
+  fn matches_scaffolded_app_shell() {
+    let app_shell: AppShell = generate_app_shell(&["page_one".to_string(), "page_two".to_string()]);
+    assert_eq!(app_shell.controller, include_str!("../template/project/frontend/src/components/Controller.tsx"));
+    assert_eq!(app_shell.page_links, include_str!("../template/project/frontend/src/routes.ts"));
+    assert_eq!(app_shell.not_found, include_str!("../template/project/frontend/src/components/pages/NotFound.tsx"));
+  }
+}
@@ -0,0 +1,216 @@
+use crate::helpers::command_line::{confirm_safe_code, print_safety_report, PrintCommand};
+use crate::helpers::safety::SafetyReport;
+use serde::{Serialize, Deserialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+
+// Requests made by this process, told apart when asked for within the same millisecond
+static REQUEST_COUNT: AtomicU64 = AtomicU64::new(0);
+
+
+// How AI written code gets approved before it runs
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalPolicy {
+  #[default]
+  Interactive,
+  AutoApprove,
+  AutoApproveIfClean,
+  Deny,
+  ApproveViaFile,
+}
+
+
+// Approval decision, logged with the run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+  pub position: String,
+  pub policy: ApprovalPolicy,
+  pub is_approved: bool,
+  pub findings: Vec<String>,
+  pub reason: String,
+}
+
+
+// Applies the run's approval policy and keeps a log of every decision
+// Clones share the same log so decisions made inside agents reach the run record
+#[derive(Debug, Clone)]
+pub struct Approver {
+  pub policy: ApprovalPolicy,
+  pub request_dir: PathBuf,
+  pub file_timeout: Duration,
+  log: Arc<Mutex<Vec<ApprovalRecord>>>,
+}
+
+impl Approver {
+  pub fn new(policy: ApprovalPolicy, request_dir: &Path) -> Self {
+    Self {
+      policy,
+      request_dir: request_dir.to_path_buf(),
+      file_timeout: Duration::from_secs(60 * 60),
+      log: Arc::new(Mutex::new(vec![])),
+    }
+  }
+
+  // Carry on the log of an earlier session of the same run
+  pub fn with_records(self, records: Vec<ApprovalRecord>) -> Self {
+    *self.log.lock().expect("Approval log poisoned") = records;
+    self
+  }
+
+  // Decisions made so far
+  pub fn records(&self) -> Vec<ApprovalRecord> {
+    self.log.lock().expect("Approval log poisoned").clone()
+  }
+
+  // Decide whether code described by a report may run
+  pub async fn approve(&self, agent_pos: &str, report: &SafetyReport) -> bool {
+    let (is_approved, reason): (bool, String) = match self.policy {
+      ApprovalPolicy::Interactive => (confirm_safe_code(report), "reviewed by user".to_string()),
+      ApprovalPolicy::AutoApprove => {
+        print_safety_report(report);
+        (true, "approved without review".to_string())
+      },
+      ApprovalPolicy::AutoApproveIfClean => {
+        print_safety_report(report);
+        match report.findings.is_empty() {
+          true => (true, "safety scan clean".to_string()),
+          false => (false, "safety scan found risky code".to_string())
+        }
+      },
+      ApprovalPolicy::Deny => (false, "policy denies running AI code".to_string()),
+      ApprovalPolicy::ApproveViaFile => self.wait_for_file_decision(agent_pos, report).await,
+    };
+
+    // Log decision
+    let decision_msg: String = format!("Approval ({:?}): {} - {}", self.policy, if is_approved { "approved" } else { "denied" }, reason);
+    let print_command: PrintCommand = if is_approved { PrintCommand::UnitTest } else { PrintCommand::Issue };
+    print_command.print_agent_message(agent_pos, decision_msg.as_str());
+    self.log.lock().expect("Approval log poisoned").push(ApprovalRecord {
+      position: agent_pos.to_string(),
+      policy: self.policy,
+      is_approved,
+      findings: report.findings.iter().map(|finding| finding.to_string()).collect(),
+      reason,
+    });
+    is_approved
+  }
+
+  // Writes the report for an external reviewer, then waits for a matching .approved or .denied file
+  // Decision files older than the request are left over from before and ignored
+  async fn wait_for_file_decision(&self, agent_pos: &str, report: &SafetyReport) -> (bool, String) {
+    let request_id: String = approval_request_id(report);
+    let request_path: PathBuf = self.request_dir.join(format!("{}.md", request_id));
+    let approved_path: PathBuf = self.request_dir.join(format!("{}.approved", request_id));
+    let denied_path: PathBuf = self.request_dir.join(format!("{}.denied", request_id));
+
+    // Write request
+    let findings: String = report.findings.iter().map(|finding| format!("- {}\n", finding)).collect();
+    let request: String = format!("# {} approval\n\n## Findings\n\n{}\n## Diff\n\n```diff\n{}```\n", agent_pos, findings, report.diff);
+    if let Err(e) = fs::create_dir_all(&self.request_dir).and_then(|_| fs::write(&request_path, request)) {
+      return (false, format!("unable to write approval request: {}", e));
+    }
+    let waiting_msg: String = format!("Waiting for {:?} or {:?}", approved_path, denied_path);
+    PrintCommand::UnitTest.print_agent_message(agent_pos, waiting_msg.as_str());
+
+    // Poll for reviewer decision
+    let requested_at: SystemTime = modified_at(&request_path).unwrap_or(UNIX_EPOCH);
+    let is_decided = |path: &Path| -> bool {
+      modified_at(path).is_some_and(|decided_at| decided_at >= requested_at)
+    };
+    let started: Instant = Instant::now();
+    while started.elapsed() < self.file_timeout {
+      if is_decided(&approved_path) {
+        return (true, format!("approved via {:?}", approved_path));
+      }
+      if is_decided(&denied_path) {
+        return (false, format!("denied via {:?}", denied_path));
+      }
+      tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    (false, format!("no decision within {:?}", self.file_timeout))
+  }
+}
+
+
+// Request name unique to the reviewed diff and when it was asked for, so never reused by a resumed run
+fn approval_request_id(report: &SafetyReport) -> String {
+  let mut hasher: DefaultHasher = DefaultHasher::new();
+  report.diff.hash(&mut hasher);
+  let requested_at: u128 = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_millis()).unwrap_or_default();
+  let request_count: u64 = REQUEST_COUNT.fetch_add(1, Ordering::Relaxed) + 1;
+  format!("approval-{}-{}-{:016x}", requested_at, request_count, hasher.finish())
+}
+
+
+// When a file was last written, if it exists
+fn modified_at(path: &Path) -> Option<SystemTime> {
+  fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::helpers::safety::{SafetyFinding, FindingKind};
+  use std::thread;
+
+  fn report(findings: Vec<SafetyFinding>) -> SafetyReport {
+    SafetyReport { findings, diff: "+fn main() {}\n".to_string() }
+  }
+
+  #[tokio::test]
+  async fn applies_policy_and_logs_decisions() {
+    let risky: SafetyFinding = SafetyFinding { file: String::new(), kind: FindingKind::Unsafe, line: 3, detail: "unsafe block".to_string() };
+    let approver: Approver = Approver::new(ApprovalPolicy::AutoApproveIfClean, &std::env::temp_dir());
+    let shared: Approver = approver.clone();
+    assert!(approver.approve("Backend Developer", &report(vec![])).await);
+    assert!(!shared.approve("Backend Developer", &report(vec![risky])).await);
+    assert!(!Approver::new(ApprovalPolicy::Deny, &std::env::temp_dir()).approve("Backend Developer", &report(vec![])).await);
+
+    let records: Vec<ApprovalRecord> = approver.records();
+    assert_eq!(records.len(), 2);
+    assert!(records[0].is_approved);
+    assert_eq!(records[1].findings, vec!["line 3: Unsafe: unsafe block".to_string()]);
+  }
+
+  #[tokio::test]
+  async fn waits_for_reviewer_file() {
+    let dir: PathBuf = std::env::temp_dir().join(format!("gippity-approval-{}", std::process::id()));
+    let approver: Approver = Approver::new(ApprovalPolicy::ApproveViaFile, &dir);
+
+    // Reviewer approves once the request shows up
+    let reviewer_dir: PathBuf = dir.clone();
+    let reviewer: thread::JoinHandle<(PathBuf, String)> = thread::spawn(move || {
+      loop {
+        let requests: Vec<PathBuf> = fs::read_dir(&reviewer_dir).into_iter().flatten().flatten()
+          .map(|entry| entry.path())
+          .filter(|path| path.extension().is_some_and(|extension| extension == "md"))
+          .collect();
+        if let Some(request_path) = requests.into_iter().next() {
+          let request: String = fs::read_to_string(&request_path).unwrap();
+          fs::write(request_path.with_extension("approved"), "").unwrap();
+          return (request_path, request);
+        }
+        thread::sleep(Duration::from_millis(50));
+      }
+    });
+    let is_approved: bool = approver.approve("Backend Developer", &report(vec![])).await;
+    let (request_path, request): (PathBuf, String) = reviewer.join().unwrap();
+
+    // A second request for the same diff gets its own name
+    let second_id: String = approval_request_id(&report(vec![]));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(is_approved);
+    assert!(request.contains("+fn main() {}"));
+    assert_ne!(request_path.file_stem().unwrap().to_string_lossy(), second_id);
+  }
+}
@@ -0,0 +1,82 @@
+use crate::helpers::dependencies::{AllowedCrate, CRATE_ALLOWLIST};
+use crate::helpers::modules::{BackendModule, ModulePlan};
+
+
+// Module of the generated backend holding password hashing, login tokens and the auth guard
+pub const AUTH_MODULE_NAME: &str = "auth";
+
+// Middleware protected routes are wrapped in
+pub const AUTH_GUARD: &str = "require_auth";
+
+// Fixed code of the auth module, saved as is rather than written by the model
+pub const AUTH_MODULE_CODE: &str = include_str!("../template/auth/auth.rs");
+
+// How handlers and main() use the auth module, shown to the model
+pub const AUTH_USAGE: &str = include_str!("../template/auth/usage.rs");
+
+
+// Crates the auth module needs on top of the backend template
+pub fn auth_crates() -> Vec<AllowedCrate> {
+  let names: [&str; 3] = ["argon2", "jsonwebtoken", "rand"];
+  CRATE_ALLOWLIST.iter().filter(|allowed| names.contains(&allowed.name)).copied().collect()
+}
+
+
+// Auth module as it appears in the module plan, so other modules are written against its real signatures
+pub fn auth_module() -> BackendModule {
+  let signatures: [&str; 9] = [
+    "pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error>",
+    "pub fn verify_password(password: &str, password_hash: &str) -> bool",
+    "pub struct Claims { pub sub: String, pub exp: u64, pub jti: String }",
+    "pub struct AuthKeys",
+    "impl AuthKeys { pub fn from_env() -> Self }",
+    "impl AuthKeys { pub fn issue_token(&self, username: &str) -> Result<String, jsonwebtoken::errors::Error> }",
+    "impl AuthKeys { pub fn verify_token(&self, token: &str) -> Option<Claims> }",
+    "impl AuthKeys { pub fn revoke(&self, claims: &Claims) }",
+    "pub async fn require_auth(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error>",
+  ];
+  BackendModule {
+    name: AUTH_MODULE_NAME.to_string(),
+    purpose: "argon2 password hashing, JWT login tokens revoked on logout, and the require_auth guard for protected routes".to_string(),
+    signatures: signatures.iter().map(|signature| signature.to_string()).collect(),
+  }
+}
+
+
+// Puts the fixed auth module first in a plan, in place of any auth module the model planned itself
+pub fn with_auth_module(module_plan: ModulePlan) -> ModulePlan {
+  let mut modules: Vec<BackendModule> = vec![auth_module()];
+  modules.extend(module_plan.modules.into_iter().filter(|module| module.name != AUTH_MODULE_NAME));
+  ModulePlan { modules }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn plans_fixed_auth_module() {
+    let planned: ModulePlan = serde_json::from_str(r#"{"modules": [
+      {"name": "models", "purpose": "shared types", "signatures": []},
+      {"name": "auth", "purpose": "sessions", "signatures": ["pub fn login()"]}
+    ]}"#).unwrap();
+    let module_plan: ModulePlan = with_auth_module(planned);
+    let names: Vec<&str> = module_plan.modules.iter().map(|module| module.name.as_str()).collect();
+    assert_eq!(names, vec!["auth", "models"]);
+    assert!(module_plan.validate().is_ok());
+
+    // Every public function of the template is in the plan
+    let file: syn::File = syn::parse_file(AUTH_MODULE_CODE).unwrap();
+    let signatures: String = module_plan.signatures();
+    for item in file.items {
+      if let syn::Item::Fn(item_fn) = item {
+        let is_public: bool = matches!(item_fn.vis, syn::Visibility::Public(_));
+        assert_eq!(signatures.contains(&format!("fn {}(", item_fn.sig.ident)), is_public);
+      }
+    }
+    assert!(signatures.contains(AUTH_GUARD));
+    assert_eq!(auth_crates().len(), 3);
+  }
+}
@@ -2,6 +2,8 @@ use crossterm::{
   style::{Color, ResetColor, SetForegroundColor},
   ExecutableCommand,
 };
+use crate::models::agents_manager::run_record::RunRecord;
+use crate::helpers::safety::SafetyReport;
 use std::io::{stdin, stdout};
 
 
@@ -57,7 +59,7 @@ pub fn get_user_response(question: &str) -> String {
   stdout
       .execute(SetForegroundColor(Color::Blue))
       .unwrap();
-  println!("");
+  println!();
   println!("{}", question);
 
   // Reset color
@@ -72,20 +74,41 @@ pub fn get_user_response(question: &str) -> String {
       .expect("Failed to read response");
 
   // Trim whitespace and return
-  return user_response.trim().to_string();
+  user_response.trim().to_string()
+}
+
+
+// Show the code diff and anything risky the scanner found
+pub fn print_safety_report(report: &SafetyReport) {
+  let mut stdout: std::io::Stdout = stdout();
+  println!("{}", report.diff);
+
+  // Print findings in colour
+  if report.findings.is_empty() {
+    stdout.execute(SetForegroundColor(Color::Green)).unwrap();
+    println!("Safety scan: nothing risky found");
+  } else {
+    stdout.execute(SetForegroundColor(Color::Red)).unwrap();
+    println!("Safety scan: {} finding(s)", report.findings.len());
+    for finding in &report.findings {
+      println!("  {}", finding);
+    }
+  }
+  stdout.execute(ResetColor).unwrap();
 }
 
 
 // User response on safe code
-pub fn confirm_safe_code() -> bool {
+pub fn confirm_safe_code(report: &SafetyReport) -> bool {
   let mut stdout: std::io::Stdout = stdout();
+  print_safety_report(report);
   loop {
 
     // Print the question in a specific color
     stdout
         .execute(SetForegroundColor(Color::Blue))
         .unwrap();
-    println!("");
+    println!();
     print!("You are about to run code written entirely by AI. ");
     println!("Review the code and confirm your view:");
 
@@ -128,3 +151,26 @@ pub fn confirm_safe_code() -> bool {
     }
   }
 }
+
+
+// Print saved run details
+pub fn print_run_summary(run: &RunRecord) {
+  println!("Run: {}", run.run_id);
+  println!("Request: {}", run.user_request);
+  println!("Model: {} ({} LLM calls made, budget: {:?})", run.model, run.llm_calls_made, run.budget);
+  for record in &run.agents {
+    println!("  {}: {:?}", record.position, record.outcome);
+  }
+  println!("Approval policy: {:?}", run.approval_policy);
+  println!("Quality gates: {:?}", run.gates);
+  if let Some(storage) = run.storage {
+    println!("Storage: {:?}", storage);
+  }
+  for approval in &run.approvals {
+    println!("  {}: {} ({})", approval.position, if approval.is_approved { "approved" } else { "denied" }, approval.reason);
+    for finding in &approval.findings {
+      println!("    {}", finding);
+    }
+  }
+  println!("{}", serde_json::to_string_pretty(&run.factsheet).expect("Failed to encode factsheet"));
+}
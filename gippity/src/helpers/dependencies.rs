@@ -0,0 +1,140 @@
+use serde_json::Value;
+use std::path::Path;
+
+
+// Crate the backend agent may add to the generated Cargo.toml, pinned to a version the vendored registry holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AllowedCrate {
+  pub name: &'static str,
+  pub version: &'static str,
+  pub features: &'static [&'static str],
+  pub purpose: &'static str,
+}
+
+
+// Extra crates on offer beyond those in the backend template
+pub const CRATE_ALLOWLIST: &[AllowedCrate] = &[
+  AllowedCrate { name: "chrono", version: "0.4.31", features: &["serde"], purpose: "dates and times" },
+  AllowedCrate { name: "uuid", version: "1.3.2", features: &["v4", "serde"], purpose: "unique ids" },
+  AllowedCrate { name: "argon2", version: "0.5.2", features: &[], purpose: "password hashing" },
+  AllowedCrate { name: "jsonwebtoken", version: "9.3.0", features: &[], purpose: "login tokens" },
+  AllowedCrate { name: "sqlx", version: "0.7.3", features: &["runtime-tokio", "sqlite"], purpose: "sqlite database access" },
+  AllowedCrate { name: "rusqlite", version: "0.29.0", features: &["bundled"], purpose: "embedded sqlite database" },
+  AllowedCrate { name: "sled", version: "0.34.7", features: &[], purpose: "embedded key value database" },
+  AllowedCrate { name: "rand", version: "0.8.5", features: &[], purpose: "random numbers and tokens" },
+  AllowedCrate { name: "regex", version: "1.10.2", features: &[], purpose: "input validation" },
+  AllowedCrate { name: "thiserror", version: "1.0.50", features: &[], purpose: "error types" },
+];
+
+
+// Allowlist as shown to the model when it picks crates
+pub fn allowlist_description() -> String {
+  CRATE_ALLOWLIST.iter()
+    .map(|allowed| format!("{} ({})", allowed.name, allowed.purpose))
+    .collect::<Vec<String>>()
+    .join(", ")
+}
+
+
+// Crates picked by the model from a JSON list of names
+// Anything not on the allowlist is returned separately so it can be reported and left out
+pub fn select_crates(ai_response: &str) -> Result<(Vec<AllowedCrate>, Vec<String>), String> {
+  let names: Vec<String> = serde_json::from_str(ai_response).map_err(|e| e.to_string())?;
+  let mut selected: Vec<AllowedCrate> = vec![];
+  let mut rejected: Vec<String> = vec![];
+  for name in names {
+    match CRATE_ALLOWLIST.iter().find(|allowed| allowed.name == name.trim()) {
+      Some(allowed) if !selected.contains(allowed) => selected.push(*allowed),
+      Some(_) => {},
+      None => rejected.push(name)
+    }
+  }
+  Ok((selected, rejected))
+}
+
+
+// Names of crates already in the [dependencies] table of a Cargo.toml
+fn declared_dependencies(cargo_toml: &str) -> Vec<String> {
+  let mut names: Vec<String> = vec![];
+  let mut is_dependencies: bool = false;
+  for line in cargo_toml.lines().map(str::trim) {
+    if line.starts_with('[') {
+      is_dependencies = line == "[dependencies]";
+      continue;
+    }
+    if let Some((name, _)) = line.split_once('=').filter(|_| is_dependencies && !line.starts_with('#')) {
+      names.push(name.trim().trim_matches('"').replace('_', "-"));
+    }
+  }
+  names
+}
+
+
+// Adds crates to the [dependencies] table with exact version pins, keeping any already declared
+pub fn add_dependencies(cargo_toml: &str, crates: &[AllowedCrate]) -> String {
+  let declared: Vec<String> = declared_dependencies(cargo_toml);
+  let new_lines: Vec<String> = crates.iter()
+    .filter(|allowed| !declared.contains(&allowed.name.replace('_', "-")))
+    .map(|allowed| match allowed.features.is_empty() {
+      true => format!("{} = \"={}\"", allowed.name, allowed.version),
+      false => format!("{} = {{ version = \"={}\", features = {} }}", allowed.name, allowed.version, Value::from(allowed.features.to_vec()))
+    })
+    .collect();
+  if new_lines.is_empty() {
+    return cargo_toml.to_string();
+  }
+
+  // Dependencies go at the end of their table, which is created if missing
+  let mut lines: Vec<String> = cargo_toml.lines().map(String::from).collect();
+  let table_start: usize = match lines.iter().position(|line| line.trim() == "[dependencies]") {
+    Some(i) => i,
+    None => {
+      lines.extend([String::new(), "[dependencies]".to_string()]);
+      lines.len() - 1
+    }
+  };
+  let mut table_end: usize = lines[table_start + 1..].iter()
+    .position(|line| line.trim().starts_with('['))
+    .map_or(lines.len(), |i| table_start + 1 + i);
+  while table_end > table_start + 1 && lines[table_end - 1].trim().is_empty() {
+    table_end -= 1;
+  }
+  lines.splice(table_end..table_end, new_lines);
+  format!("{}\n", lines.join("\n"))
+}
+
+
+// Cargo config which resolves crates.io dependencies from a local vendored directory
+pub fn vendor_config(vendor_dir: &Path) -> String {
+  format!("[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = {}\n",
+    Value::from(vendor_dir.to_string_lossy().to_string()))
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  const CARGO_TOML: &str = "[package]\nname = \"backend\"\n\n[dependencies]\nserde = \"1.0.160\"\nuuid = { version = \"1.3.2\", features = [\"v4\"] }\n\n[dev-dependencies]\nactix-rt = \"2\"\n";
+
+  #[test]
+  fn selects_allowed_crates() {
+    let (selected, rejected) = select_crates("[\"chrono\", \"openssl\", \"chrono\", \"argon2\"]").unwrap();
+    assert_eq!(selected.iter().map(|allowed| allowed.name).collect::<Vec<&str>>(), vec!["chrono", "argon2"]);
+    assert_eq!(rejected, vec!["openssl"]);
+    assert!(select_crates("chrono and argon2").is_err());
+    assert!(allowlist_description().contains("argon2 (password hashing)"));
+  }
+
+  #[test]
+  fn pins_new_dependencies() {
+    let (selected, _) = select_crates("[\"uuid\", \"argon2\", \"chrono\"]").unwrap();
+    assert_eq!(add_dependencies(CARGO_TOML, &selected), "[package]\nname = \"backend\"\n\n[dependencies]\nserde = \"1.0.160\"\n\
+      uuid = { version = \"1.3.2\", features = [\"v4\"] }\nargon2 = \"=0.5.2\"\nchrono = { version = \"=0.4.31\", features = [\"serde\"] }\n\n\
+      [dev-dependencies]\nactix-rt = \"2\"\n");
+    assert_eq!(add_dependencies(CARGO_TOML, &[]), CARGO_TOML);
+    assert_eq!(vendor_config(Path::new("/opt/vendor")),
+      "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = \"/opt/vendor\"\n");
+  }
+}
@@ -0,0 +1,478 @@
+use crate::models::agents::agent_traits::RouteObject;
+use serde_json::{json, Map, Value};
+use reqwest::{Client, Method};
+use std::collections::HashMap;
+
+
+// Placeholder values used to fill in sample request bodies
+const SAMPLE_NUMBER: u64 = 1;
+const SAMPLE_STRING: &str = "test";
+
+// Longest response body kept for a failing endpoint
+const MAX_BODY_LEN: usize = 500;
+
+// Marks the row of the matrix for calling a protected route without a token
+const NO_AUTH_LABEL: &str = "(no auth)";
+
+
+// Result of calling a single endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestOutcome {
+  Passed,
+  Failed(String),
+}
+
+
+// One row of the pass/fail matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointResult {
+  pub method: String,
+  pub route: String,
+  pub url: String,
+  pub status: Option<u16>,
+  pub body: String,
+  pub outcome: TestOutcome,
+}
+
+
+// Results for every route the backend exposes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EndpointReport {
+  pub results: Vec<EndpointResult>,
+}
+
+impl EndpointReport {
+
+  // Confirms every endpoint passed
+  pub fn is_passing(&self) -> bool {
+    self.results.iter().all(|result| result.outcome == TestOutcome::Passed)
+  }
+
+  // Endpoints which failed
+  pub fn failures(&self) -> Vec<&EndpointResult> {
+    self.results.iter().filter(|result| result.outcome != TestOutcome::Passed).collect()
+  }
+
+  // Failing endpoints with their status and response body, for fixing the code
+  pub fn failure_summary(&self) -> String {
+    self.failures().iter().map(|result| {
+      let status: String = result.status.map(|status| status.to_string()).unwrap_or("no response".to_string());
+      let reason: &str = match &result.outcome {
+        TestOutcome::Failed(reason) => reason,
+        TestOutcome::Passed => "",
+      };
+      format!("{} {} ({}) failed: {}. Status: {}. Response body: {:?}\n",
+        result.method.to_uppercase(), result.route, result.url, reason, status, result.body)
+    }).collect()
+  }
+
+  // Pass/fail matrix, one line per route
+  pub fn matrix(&self) -> String {
+    let mut matrix: String = format!("{:<8} {:<30} {:<7} RESULT\n", "METHOD", "ROUTE", "STATUS");
+    for result in &self.results {
+      let status: String = result.status.map(|status| status.to_string()).unwrap_or("-".to_string());
+      let outcome: String = match &result.outcome {
+        TestOutcome::Passed => "PASS".to_string(),
+        TestOutcome::Failed(reason) => format!("FAIL: {}", reason),
+      };
+      matrix.push_str(&format!("{:<8} {:<30} {:<7} {}\n", result.method.to_uppercase(), result.route, status, outcome));
+    }
+    matrix
+  }
+}
+
+
+// Builds a request body matching a schema such as {"id": "number", "name": "string"}
+pub fn sample_from_schema(schema: &Value) -> Value {
+  match schema {
+    Value::String(kind) => match kind.as_str() {
+      "number" => json!(SAMPLE_NUMBER),
+      "string" => json!(SAMPLE_STRING),
+      "bool" => json!(true),
+      "object" => json!({}),
+      "Array" => json!([]),
+      _ => Value::Null
+    },
+    Value::Array(items) => match items.first() {
+      Some(item) => json!([sample_from_schema(item)]),
+      None => json!([])
+    },
+    Value::Object(fields) => {
+      let mut object: Map<String, Value> = Map::new();
+      for (name, field_schema) in fields {
+        object.insert(name.clone(), sample_from_schema(field_schema));
+      }
+      Value::Object(object)
+    },
+    _ => Value::Null
+  }
+}
+
+
+// Checks a response body has the shape of a schema
+// Extra fields are allowed, missing or mistyped ones are not
+pub fn check_schema(schema: &Value, body: &Value, at: &str) -> Result<(), String> {
+  let is_match: bool = match schema {
+    Value::String(kind) => match kind.as_str() {
+      "number" => body.is_number(),
+      "string" => body.is_string(),
+      "bool" => body.is_boolean(),
+      "object" => body.is_object(),
+      "Array" => body.is_array(),
+      _ => true
+    },
+    Value::Array(items) => {
+      let Some(body_items) = body.as_array() else {
+        return Err(format!("{} should be a list", at));
+      };
+      if let Some(item_schema) = items.first() {
+        for (i, item) in body_items.iter().enumerate() {
+          check_schema(item_schema, item, &format!("{}[{}]", at, i))?;
+        }
+      }
+      true
+    },
+    Value::Object(fields) => {
+      let Some(body_fields) = body.as_object() else {
+        return Err(format!("{} should be an object", at));
+      };
+      for (name, field_schema) in fields {
+        match body_fields.get(name) {
+          Some(field) => check_schema(field_schema, field, &format!("{}.{}", at, name))?,
+          None => return Err(format!("{}.{} is missing", at, name)),
+        }
+      }
+      true
+    },
+    _ => true
+  };
+  match is_match {
+    true => Ok(()),
+    false => Err(format!("{} should be {}", at, schema)),
+  }
+}
+
+
+// Order of login routes: register and log in before anything else, and log out once everything else is done
+fn auth_order(route: &str) -> u8 {
+  match route {
+    route if route.ends_with("/register") => 0,
+    route if route.ends_with("/login") => 1,
+    route if route.ends_with("/logout") => 3,
+    _ => 2
+  }
+}
+
+
+// Order routes are called in: create, read, update then delete
+fn method_order(method: &str) -> u8 {
+  match method {
+    "post" => 0,
+    "get" => 1,
+    "put" | "patch" => 2,
+    "delete" => 3,
+    _ => 4
+  }
+}
+
+
+// Part of a route before any path parameters, such as "/task" for "/task/{id}"
+fn route_base(route: &str) -> &str {
+  route.split("/{").next().unwrap_or(route)
+}
+
+
+// Fills in {param} path parameters from resources created earlier, falling back to sample values
+pub fn fill_path(route: &str, created: &HashMap<String, Value>) -> String {
+  let resource: Option<&Value> = created.get(route_base(route));
+  let mut filled: String = String::new();
+  let mut rest: &str = route;
+  while let Some(start) = rest.find('{') {
+    let Some(end) = rest[start..].find('}') else {
+      break;
+    };
+    let param: &str = &rest[start + 1..start + end];
+    let value: Value = resource
+      .and_then(|resource| resource.get(param).or_else(|| resource.get("id")))
+      .cloned()
+      .unwrap_or(json!(SAMPLE_NUMBER));
+    filled.push_str(&rest[..start]);
+    filled.push_str(&match value {
+      Value::String(text) => text,
+      other => other.to_string(),
+    });
+    rest = &rest[start + end + 1..];
+  }
+  filled.push_str(rest);
+  filled
+}
+
+
+// Sends a route's request, with a sample body when the route takes one and a bearer token when logged in
+// Returns the status and response body
+async fn send_request(client: &Client, url: &str, route: &RouteObject, token: Option<&str>) -> Result<(u16, String), String> {
+  let method: Method = Method::from_bytes(route.method.to_uppercase().as_bytes())
+    .map_err(|_| format!("unknown method {}", route.method))?;
+  let request_body: Value = sample_from_schema(&route.request_body);
+  let mut request: reqwest::RequestBuilder = client.request(method, url);
+  if !request_body.is_null() {
+    request = request.json(&request_body);
+  }
+  if let Some(token) = token {
+    request = request.bearer_auth(token);
+  }
+  let response: reqwest::Response = request.send().await.map_err(|e| format!("request failed: {}", e))?;
+  let status: u16 = response.status().as_u16();
+  Ok((status, response.text().await.unwrap_or_default()))
+}
+
+
+// Calls a single endpoint and checks its status and response body
+// A token in the response, such as from logging in, is kept for calling protected routes
+async fn test_endpoint(client: &Client, base_url: &str, route: &RouteObject, created: &mut HashMap<String, Value>, token: &mut Option<String>) -> EndpointResult {
+  let url: String = format!("{}{}", base_url, fill_path(&route.route, created));
+  let mut result: EndpointResult = EndpointResult {
+    method: route.method.clone(),
+    route: route.route.clone(),
+    url: url.clone(),
+    status: None,
+    body: String::new(),
+    outcome: TestOutcome::Passed,
+  };
+
+  // Send request
+  let (status, response_text): (u16, String) = match send_request(client, &url, route, token.as_deref()).await {
+    Ok(response) => response,
+    Err(reason) => {
+      result.outcome = TestOutcome::Failed(reason);
+      return result;
+    }
+  };
+  result.status = Some(status);
+  result.body = response_text.chars().take(MAX_BODY_LEN).collect();
+  if !(200..300).contains(&status) {
+    result.outcome = TestOutcome::Failed(format!("unexpected status {}", status));
+    return result;
+  }
+
+  // Remember what was created so later routes can refer to it
+  let request_body: Value = sample_from_schema(&route.request_body);
+  if route.method == "post" && request_body.is_object() {
+    let created_body: Value = serde_json::from_str(&response_text)
+      .ok()
+      .filter(|body: &Value| body.get("id").is_some())
+      .unwrap_or(request_body);
+    created.insert(route_base(&route.route).to_string(), created_body);
+  }
+
+  // Log in with any token handed back
+  let response_token: Option<String> = serde_json::from_str::<Value>(&response_text).ok()
+    .and_then(|body| body.get("token").and_then(Value::as_str).map(String::from));
+  if response_token.is_some() {
+    *token = response_token;
+  }
+
+  // Check response body against its schema
+  let has_response_schema: bool = route.response.is_object() || route.response.is_array();
+  if has_response_schema {
+    let check: Result<(), String> = match serde_json::from_str::<Value>(&response_text) {
+      Ok(body) => check_schema(&route.response, &body, "response"),
+      Err(_) => Err("response is not json".to_string()),
+    };
+    if let Err(reason) = check {
+      result.outcome = TestOutcome::Failed(reason);
+    }
+  }
+  result
+}
+
+
+// Calls a protected route without a token, which the auth guard should reject with 401 or 403
+async fn test_rejects_missing_auth(client: &Client, base_url: &str, route: &RouteObject, created: &HashMap<String, Value>) -> EndpointResult {
+  let url: String = format!("{}{}", base_url, fill_path(&route.route, created));
+  let mut result: EndpointResult = EndpointResult {
+    method: route.method.clone(),
+    route: format!("{} {}", route.route, NO_AUTH_LABEL),
+    url: url.clone(),
+    status: None,
+    body: String::new(),
+    outcome: TestOutcome::Passed,
+  };
+  match send_request(client, &url, route, None).await {
+    Ok((status, response_text)) => {
+      result.status = Some(status);
+      result.body = response_text.chars().take(MAX_BODY_LEN).collect();
+      if status != 401 && status != 403 {
+        result.outcome = TestOutcome::Failed(format!("protected route answered {} without auth", status));
+      }
+    },
+    Err(reason) => result.outcome = TestOutcome::Failed(reason)
+  }
+  result
+}
+
+
+// Exercises every route: registers and logs in, creates resources, then reads, updates and deletes them
+// Protected routes are first called without a token to check they are rejected
+pub async fn run_endpoint_tests(client: &Client, base_url: &str, routes: &[RouteObject]) -> EndpointReport {
+  let mut ordered: Vec<&RouteObject> = routes.iter().collect();
+  ordered.sort_by_key(|route| (auth_order(&route.route), method_order(&route.method), route.route.contains('{')));
+
+  let mut created: HashMap<String, Value> = HashMap::new();
+  let mut token: Option<String> = None;
+  let mut report: EndpointReport = EndpointReport::default();
+  for route in ordered {
+    if route.is_protected {
+      report.results.push(test_rejects_missing_auth(client, base_url, route, &created).await);
+    }
+    report.results.push(test_endpoint(client, base_url, route, &mut created, &mut token).await);
+  }
+  report
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+
+  fn route(method: &str, path: &str, request_body: Value, response: Value) -> RouteObject {
+    RouteObject {
+      is_route_dynamic: path.contains('{'),
+      method: method.to_string(),
+      request_body,
+      response,
+      route: path.to_string(),
+      description: None,
+      is_protected: false,
+      path_params: Map::new(),
+      request_type: None,
+      response_type: None,
+    }
+  }
+
+  #[test]
+  fn samples_and_checks_schemas() {
+    let schema: Value = json!({"id": "number", "name": "string", "tags": ["string"], "done": "bool"});
+    let sample: Value = sample_from_schema(&schema);
+    assert_eq!(sample, json!({"id": 1, "name": "test", "tags": ["test"], "done": true}));
+    assert!(check_schema(&schema, &sample, "response").is_ok());
+    assert_eq!(check_schema(&schema, &json!({"id": "1", "name": "a", "tags": [], "done": true}), "response"),
+      Err("response.id should be \"number\"".to_string()));
+    assert_eq!(check_schema(&json!([schema]), &json!([{"id": 1, "done": true, "tags": []}]), "response"),
+      Err("response[0].name is missing".to_string()));
+  }
+
+  #[test]
+  fn fills_path_parameters_from_created_resources() {
+    let mut created: HashMap<String, Value> = HashMap::new();
+    created.insert("/quotes".to_string(), json!({"id": 7, "symbol": "GBPUSD"}));
+    assert_eq!(fill_path("/quotes/{id}", &created), "/quotes/7");
+    assert_eq!(fill_path("/quotes/{symbol}/history", &created), "/quotes/GBPUSD/history");
+    assert_eq!(fill_path("/users/{id}", &created), "/users/1");
+  }
+
+  #[tokio::test]
+  async fn runs_routes_in_crud_order() {
+
+    // Server which only knows about task 1 once it has been created, and returns a bad list
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url: String = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+      let mut is_created: bool = false;
+      loop {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buffer: Vec<u8> = vec![0; 4096];
+        let read: usize = socket.read(&mut buffer).await.unwrap();
+        let request: String = String::from_utf8_lossy(&buffer[..read]).to_string();
+        let (status, body): (&str, &str) = match request.lines().next().unwrap_or_default() {
+          line if line.starts_with("POST /task ") => { is_created = true; ("200 OK", "") },
+          line if line.starts_with("GET /task/1 ") && is_created => ("200 OK", "{\"id\":1,\"name\":\"test\"}"),
+          line if line.starts_with("GET /task ") => ("200 OK", "[{\"id\":1}]"),
+          line if line.starts_with("DELETE /task/1 ") => ("200 OK", ""),
+          _ => ("404 Not Found", ""),
+        };
+        let response: String = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+        socket.write_all(response.as_bytes()).await.unwrap();
+      }
+    });
+
+    let task: Value = json!({"id": "number", "name": "string"});
+    let routes: Vec<RouteObject> = vec![
+      route("delete", "/task/{id}", json!("None"), json!("None")),
+      route("get", "/task/{id}", json!("None"), task.clone()),
+      route("get", "/task", json!("None"), json!([task.clone()])),
+      route("put", "/task/{id}", task.clone(), json!("None")),
+      route("post", "/task", task, json!("None")),
+    ];
+    let report: EndpointReport = run_endpoint_tests(&Client::new(), &base_url, &routes).await;
+    let matrix: Vec<(String, String, Option<u16>, bool)> = report.results.iter()
+      .map(|result| (result.method.clone(), result.route.clone(), result.status, result.outcome == TestOutcome::Passed)).collect();
+
+    assert_eq!(matrix, vec![
+      ("post".to_string(), "/task".to_string(), Some(200), true),
+      ("get".to_string(), "/task".to_string(), Some(200), false),
+      ("get".to_string(), "/task/{id}".to_string(), Some(200), true),
+      ("put".to_string(), "/task/{id}".to_string(), Some(404), false),
+      ("delete".to_string(), "/task/{id}".to_string(), Some(200), true),
+    ]);
+    assert_eq!(report.failures().len(), 2);
+    assert!(report.matrix().contains("FAIL: response[0].name is missing"));
+    assert!(report.failure_summary().contains("PUT /task/{id}"));
+    assert!(report.failure_summary().contains("Response body: \"[{\\\"id\\\":1}]\""));
+  }
+
+  #[tokio::test]
+  async fn logs_in_and_checks_protected_routes() {
+
+    // Server where /task needs the token handed out by /login, and /stats forgot its auth guard
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url: String = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+      loop {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buffer: Vec<u8> = vec![0; 4096];
+        let read: usize = socket.read(&mut buffer).await.unwrap();
+        let request: String = String::from_utf8_lossy(&buffer[..read]).to_string();
+        let is_logged_in: bool = request.to_lowercase().contains("authorization: bearer abc");
+        let (status, body): (&str, &str) = match request.lines().next().unwrap_or_default() {
+          line if line.starts_with("POST /register ") => ("200 OK", ""),
+          line if line.starts_with("POST /login ") => ("200 OK", "{\"token\":\"abc\"}"),
+          line if line.starts_with("GET /stats ") => ("200 OK", ""),
+          _ if !is_logged_in => ("401 Unauthorized", ""),
+          line if line.starts_with("GET /task ") || line.starts_with("POST /logout ") => ("200 OK", "[]"),
+          _ => ("404 Not Found", ""),
+        };
+        let response: String = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+        socket.write_all(response.as_bytes()).await.unwrap();
+      }
+    });
+
+    let user: Value = json!({"username": "string", "password": "string"});
+    let protected = |method: &str, path: &str| RouteObject { is_protected: true, ..route(method, path, json!("None"), json!("None")) };
+    let routes: Vec<RouteObject> = vec![
+      protected("post", "/logout"),
+      protected("get", "/task"),
+      route("post", "/login", user.clone(), json!("not_provided")),
+      protected("get", "/stats"),
+      route("post", "/register", user, json!("None")),
+    ];
+    let report: EndpointReport = run_endpoint_tests(&Client::new(), &base_url, &routes).await;
+    let matrix: Vec<(String, Option<u16>, bool)> = report.results.iter()
+      .map(|result| (format!("{} {}", result.method, result.route), result.status, result.outcome == TestOutcome::Passed)).collect();
+
+    assert_eq!(matrix, vec![
+      ("post /register".to_string(), Some(200), true),
+      ("post /login".to_string(), Some(200), true),
+      ("get /task (no auth)".to_string(), Some(401), true),
+      ("get /task".to_string(), Some(200), true),
+      ("get /stats (no auth)".to_string(), Some(200), false),
+      ("get /stats".to_string(), Some(200), true),
+      ("post /logout (no auth)".to_string(), Some(401), true),
+      ("post /logout".to_string(), Some(200), true),
+    ]);
+    assert!(report.failure_summary().contains("protected route answered 200 without auth"));
+  }
+}
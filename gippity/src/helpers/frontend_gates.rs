@@ -0,0 +1,154 @@
+use crate::helpers::quality_gates::Diagnostic;
+use crate::helpers::sandbox::SandboxCommand;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+
+// Longest raw output kept when a gate fails without any diagnostics
+const MAX_RAW_OUTPUT_LEN: usize = 2000;
+
+// ESLint severity of an error, warnings being 1
+const ESLINT_ERROR: u64 = 2;
+
+
+// Checks run on the frontend before it is built, as the build stops at the first failing step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrontendGate {
+  TypeCheck,
+  Lint,
+}
+
+impl FrontendGate {
+
+  // Every gate, in the order they are run
+  pub fn all() -> [FrontendGate; 2] {
+    [FrontendGate::TypeCheck, FrontendGate::Lint]
+  }
+
+  // Command which runs the gate on a frontend project with its packages installed
+  pub fn command(&self, dir: &Path) -> SandboxCommand {
+    match self {
+      FrontendGate::TypeCheck => SandboxCommand::new("yarn", &["--silent", "tsc", "--noEmit", "--pretty", "false"], dir),
+      FrontendGate::Lint => SandboxCommand::new("yarn", &["--silent", "eslint", "src", "--ext", "ts,tsx", "--format", "json"], dir),
+    }
+  }
+
+  // Problems found by a finished gate command, empty when it passed
+  pub fn diagnostics(&self, output: &Output, dir: &Path) -> Vec<Diagnostic> {
+    if output.status.success() {
+      return vec![];
+    }
+    let stdout: String = String::from_utf8_lossy(&output.stdout).to_string();
+    let diagnostics: Vec<Diagnostic> = match self {
+      FrontendGate::TypeCheck => parse_tsc_output(&stdout),
+      FrontendGate::Lint => parse_eslint_json(&stdout, dir),
+    };
+
+    // Still report a failing gate when its output could not be understood
+    if !diagnostics.is_empty() {
+      return diagnostics;
+    }
+    let raw_output: String = format!("{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    let skip: usize = raw_output.chars().count().saturating_sub(MAX_RAW_OUTPUT_LEN);
+    vec![Diagnostic {
+      file: String::new(),
+      line: 0,
+      code: Some(format!("{:?}", self).to_lowercase()),
+      message: raw_output.chars().skip(skip).collect(),
+    }]
+  }
+}
+
+
+// Errors from tsc --pretty false, such as "src/App.tsx(12,5): error TS2304: Cannot find name 'x'."
+// Indented lines which follow carry on the message of the error above them
+pub fn parse_tsc_output(stdout: &str) -> Vec<Diagnostic> {
+  let mut diagnostics: Vec<Diagnostic> = vec![];
+  for line in stdout.lines() {
+    if line.starts_with(' ') {
+      if let Some(diagnostic) = diagnostics.last_mut() {
+        diagnostic.message.push_str(&format!("\n{}", line.trim()));
+      }
+      continue;
+    }
+    let Some((location, rest)) = line.split_once("): error ") else {
+      continue;
+    };
+    let Some((file, position)) = location.rsplit_once('(') else {
+      continue;
+    };
+    let (code, message) = rest.split_once(": ").unwrap_or(("", rest));
+    diagnostics.push(Diagnostic {
+      file: file.to_string(),
+      line: position.split(',').next().and_then(|line| line.parse().ok()).unwrap_or_default(),
+      code: Some(code.to_string()).filter(|code| !code.is_empty()),
+      message: message.to_string(),
+    });
+  }
+  diagnostics
+}
+
+
+// Errors from eslint --format json, with files relative to the frontend folder
+// ESLint always gives absolute paths, so they are made relative to the folder as the filesystem resolves it
+pub fn parse_eslint_json(stdout: &str, dir: &Path) -> Vec<Diagnostic> {
+  let dir: PathBuf = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+  let json: &str = stdout.find('[').map(|start| &stdout[start..]).unwrap_or_default();
+  let Ok(results) = serde_json::from_str::<Vec<Value>>(json) else {
+    return vec![];
+  };
+  results.iter().flat_map(|result| {
+    let file_path: &str = result["filePath"].as_str().unwrap_or_default();
+    let file: String = Path::new(file_path).strip_prefix(&dir).map(|file| file.to_string_lossy().to_string()).unwrap_or(file_path.to_string());
+    result["messages"].as_array().cloned().unwrap_or_default().into_iter()
+      .filter(|message| message["severity"].as_u64() == Some(ESLINT_ERROR))
+      .map(move |message| Diagnostic {
+        file: file.clone(),
+        line: message["line"].as_u64().unwrap_or_default() as usize,
+        code: message["ruleId"].as_str().map(String::from),
+        message: message["message"].as_str().unwrap_or_default().to_string(),
+      })
+  }).collect()
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_tsc_and_eslint_output() {
+    let tsc_stdout: &str = concat!(
+      "src/components/pages/HomePage.tsx(12,5): error TS2322: Type 'string' is not assignable to type 'number'.\n",
+      "src/api/client.ts(3,1): error TS2305: Module '\"./types\"' has no exported member 'Task'.\n",
+      "  The expected type comes from property 'id'.\n",
+    );
+    let diagnostics: Vec<Diagnostic> = parse_tsc_output(tsc_stdout);
+    assert_eq!(diagnostics[0].to_string(), "src/components/pages/HomePage.tsx:12: [TS2322] Type 'string' is not assignable to type 'number'.");
+    assert_eq!(diagnostics[1].message, "Module '\"./types\"' has no exported member 'Task'.\nThe expected type comes from property 'id'.");
+
+    let eslint_stdout: &str = r#"[{"filePath":"/tmp/web/src/components/pages/HomePage.tsx","messages":[
+      {"ruleId":"@typescript-eslint/no-unused-vars","severity":2,"message":"'x' is assigned a value but never used.","line":4},
+      {"ruleId":"react-refresh/only-export-components","severity":1,"message":"Fast refresh only works when a file only exports components.","line":9}
+    ]},{"filePath":"/tmp/web/src/App.tsx","messages":[]}]"#;
+    assert_eq!(parse_eslint_json(eslint_stdout, Path::new("/tmp/web")), vec![Diagnostic {
+      file: "src/components/pages/HomePage.tsx".to_string(),
+      line: 4,
+      code: Some("@typescript-eslint/no-unused-vars".to_string()),
+      message: "'x' is assigned a value but never used.".to_string(),
+    }]);
+    assert!(parse_eslint_json("Oops! Something went wrong!", Path::new("/tmp/web")).is_empty());
+  }
+
+  #[test]
+  fn relates_eslint_files_to_relative_dir() {
+    let dir: &Path = Path::new("src/template/project/frontend");
+    let file_path: PathBuf = fs::canonicalize(dir).unwrap().join("src").join("App.tsx");
+    let eslint_stdout: String = format!(r#"[{{"filePath":{:?},"messages":[{{"ruleId":"no-undef","severity":2,"message":"'x' is not defined.","line":7}}]}}]"#, file_path);
+    let diagnostics: Vec<Diagnostic> = parse_eslint_json(&eslint_stdout, dir);
+    assert_eq!(diagnostics[0].file, "src/App.tsx");
+  }
+}
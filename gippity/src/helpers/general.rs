@@ -1,23 +1,18 @@
 use crate::models::general::llm::Message;
 use crate::apis::call_request::call_gpt;
 use crate::helpers::command_line::PrintCommand;
+use crate::helpers::workspace::Workspace;
+use crate::helpers::modules::{declare_module, ModulePlan, SourceFile, NON_SERVER_FILES};
+use crate::helpers::dependencies::{add_dependencies, vendor_config, AllowedCrate};
+use crate::helpers::storage::{StorageProfile, MIGRATIONS_BUILD_SCRIPT};
 use serde::de::DeserializeOwned;
 use reqwest::Client;
+use serde_json::Value;
 
 use std::fs;
+use std::path::{Path, PathBuf};
 
 
-// Constant Directories
-pub const BACKEND_CODE_DIR: &str = "/Users/shaun/Code/DEVELOPMENT/autogippity/website/backend";
-pub const FRONTEND_CODE_DIR: &str = "/Users/shaun/Code/DEVELOPMENT/autogippity/website/frontend";
-
-
-// Provide AI call response
-pub enum AIFuncResponse<T> {
-  Decoded(T),
-  Raw(String),
-}
-
 /// Takes in both the string version of an AI function
 /// Combines this with the user input to encourage a structured printout in a program-like response
 pub fn extend_ai_function(ai_func: fn(&str) -> &'static str, func_input: &str) -> Message {
@@ -68,7 +63,7 @@ pub async fn ai_task_request(
   };
    
   // Return agent response
-  return agent_response;
+  agent_response
 }
 
 
@@ -86,7 +81,7 @@ pub async fn ai_task_request_decoded<T: DeserializeOwned>(
   // Decode and return message
   let decoded_response: T = serde_json::from_str(agent_response.as_str())
     .expect("Failed to decode ai response from serde_json");
-  return decoded_response;
+  decoded_response
 }
 
 
@@ -98,34 +93,151 @@ pub async fn check_status_code(client: &Client, url: &str) -> Result<u16, reqwes
 
 
 // Get code template
-pub fn read_code_template_contents() -> String {
-  let path: String = format!("{}/src/codetemplate.rs", BACKEND_CODE_DIR);
-  fs::read_to_string(path).expect("Something went wrong reading the file")
+pub fn read_code_template_contents(workspace: &Workspace) -> String {
+  fs::read_to_string(workspace.code_template_path()).expect("Something went wrong reading the file")
+}
+
+// Switch the code template to a storage profile, adding its starting migrations and the build script which embeds them if missing
+pub fn save_storage_template(workspace: &Workspace, storage: StorageProfile) {
+  fs::write(workspace.code_template_path(), storage.code_template())
+    .expect("Something went wrong saving the file");
+  if !workspace.backend_build_script_path().exists() {
+    fs::write(workspace.backend_build_script_path(), MIGRATIONS_BUILD_SCRIPT).expect("Something went wrong saving the file");
+  }
+  fs::create_dir_all(workspace.migrations_dir()).expect("Something went wrong creating the folder");
+  for (file_name, contents) in storage.migrations() {
+    let path: PathBuf = workspace.migrations_dir().join(file_name);
+    if !path.exists() {
+      fs::write(path, contents).expect("Something went wrong saving the file");
+    }
+  }
+}
+
+// Get every storage migration in the order they are applied, each under its file name
+pub fn read_migrations(workspace: &Workspace) -> String {
+  let mut file_names: Vec<String> = fs::read_dir(workspace.migrations_dir())
+    .map(|entries| entries.filter_map(|entry| entry.ok()).filter_map(|entry| entry.file_name().into_string().ok()).collect())
+    .unwrap_or_default();
+  file_names.sort();
+  file_names.iter()
+    .map(|file_name| {
+      let contents: String = fs::read_to_string(workspace.migrations_dir().join(file_name)).expect("Something went wrong reading the file");
+      format!("// File: migrations/{}\n{}", file_name, contents)
+    })
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+// Save a storage migration
+pub fn save_migration(workspace: &Workspace, file_name: &str, contents: &str) {
+  fs::write(workspace.migrations_dir().join(file_name), contents)
+    .expect("Something went wrong saving the file");
+}
+
+// Get existing backend code
+pub fn read_backend_code(workspace: &Workspace) -> String {
+  fs::read_to_string(workspace.backend_main_path()).expect("Something went wrong reading the file")
 }
 
 // Save backend code
-pub fn save_backend_code(contents: &String) {
-  let path: String = format!("{}/src/main.rs", BACKEND_CODE_DIR);
-  fs::write(path, contents)
+pub fn save_backend_code(workspace: &Workspace, contents: &String) {
+  fs::write(workspace.backend_main_path(), contents)
     .expect("Something went wrong saving the file");
 }
 
+// Get backend integration tests
+pub fn read_backend_tests(workspace: &Workspace) -> String {
+  fs::read_to_string(workspace.backend_tests_path()).expect("Something went wrong reading the file")
+}
+
+// Save backend integration tests
+pub fn save_backend_tests(workspace: &Workspace, contents: &String) {
+  fs::write(workspace.backend_tests_path(), contents)
+    .expect("Something went wrong saving the file");
+}
+
+// Declare the integration tests module in the webserver code
+// Fixed code is written from scratch, so this is checked before every test run
+pub fn declare_backend_tests(workspace: &Workspace) {
+  let backend_code: String = read_backend_code(workspace);
+  save_backend_code(workspace, &declare_module(&backend_code, "api_tests", true));
+}
+
+// Get every webserver source file, main.rs first
+pub fn read_backend_sources(workspace: &Workspace) -> Vec<SourceFile> {
+  let mut file_names: Vec<String> = fs::read_dir(workspace.backend_dir.join("src"))
+    .expect("Something went wrong reading the backend folder")
+    .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+    .filter(|file_name| file_name.ends_with(".rs") && file_name != "main.rs" && !NON_SERVER_FILES.contains(&file_name.as_str()))
+    .collect();
+  file_names.sort();
+  file_names.insert(0, "main.rs".to_string());
+  file_names.into_iter().map(|file_name| {
+    let path: String = format!("src/{}", file_name);
+    let code: String = fs::read_to_string(workspace.backend_source_path(&path)).unwrap_or_default();
+    SourceFile { path, code }
+  }).collect()
+}
+
+// Save a webserver source file
+pub fn save_backend_source(workspace: &Workspace, source: &SourceFile) {
+  fs::write(workspace.backend_source_path(&source.path), &source.code)
+    .expect("Something went wrong saving the file");
+}
+
+// Save planned backend module layout
+pub fn save_module_plan(workspace: &Workspace, module_plan: &ModulePlan) {
+  let module_plan_str: String = serde_json::to_string_pretty(module_plan).expect("Failed to encode module plan");
+  fs::write(workspace.module_plan_path(), module_plan_str)
+    .expect("Something went wrong saving the file");
+}
+
+// Add pinned crates to the backend Cargo.toml
+pub fn save_backend_dependencies(workspace: &Workspace, crates: &[AllowedCrate]) {
+  let cargo_toml: String = fs::read_to_string(workspace.backend_manifest_path()).expect("Something went wrong reading the file");
+  fs::write(workspace.backend_manifest_path(), add_dependencies(&cargo_toml, crates))
+    .expect("Something went wrong saving the file");
+}
+
+// Resolve backend crates from a vendored registry instead of crates.io
+pub fn save_vendor_config(workspace: &Workspace, vendor_dir: &Path) {
+  let config_path: PathBuf = workspace.backend_cargo_config_path();
+  if let Some(parent) = config_path.parent() {
+    fs::create_dir_all(parent).expect("Something went wrong creating the folder");
+  }
+  fs::write(config_path, vendor_config(vendor_dir)).expect("Something went wrong saving the file");
+}
+
+// Get json api endpoint schema
+pub fn read_api_endpoints(workspace: &Workspace) -> String {
+  fs::read_to_string(workspace.api_endpoints_path()).expect("Something went wrong reading the file")
+}
+
 // Save json api endpoint schema
-pub fn save_api_endpoints(api_endpoints: &String) {
-  let path: String = format!("{}/api_endpoints.json", BACKEND_CODE_DIR);
-  fs::write(path, api_endpoints)
+pub fn save_api_endpoints(workspace: &Workspace, api_endpoints: &String) {
+  fs::write(workspace.api_endpoints_path(), api_endpoints)
+    .expect("Something went wrong saving the file");
+}
+
+// Get OpenAPI document of the backend
+pub fn read_openapi_spec(workspace: &Workspace) -> String {
+  fs::read_to_string(workspace.openapi_spec_path()).expect("Something went wrong reading the file")
+}
+
+// Save OpenAPI document of the backend
+pub fn save_openapi_spec(workspace: &Workspace, openapi_spec: &Value) {
+  let openapi_spec_str: String = serde_json::to_string_pretty(openapi_spec).expect("Failed to encode OpenAPI document");
+  fs::write(workspace.openapi_spec_path(), openapi_spec_str)
     .expect("Something went wrong saving the file");
 }
 
 // Save frontend code
-pub fn save_frontend_code(frontend_path: &String, contents: &String) {
-  let path: String = format!("{}{}", FRONTEND_CODE_DIR, frontend_path);
-  fs::write(path, contents)
+pub fn save_frontend_code(workspace: &Workspace, frontend_path: &str, contents: &String) {
+  fs::write(workspace.frontend_path(frontend_path), contents)
     .expect("Something went wrong saving the file");
 }
 
 // Get existing frontend code
-pub fn read_frontend_code_contents(frontend_path: &String) -> String {
-  let path: String = format!("{}{}", FRONTEND_CODE_DIR, frontend_path);
-  fs::read_to_string(path).expect("Something went wrong reading the file")
+pub fn read_frontend_code_contents(workspace: &Workspace, frontend_path: &str) -> String {
+  fs::read_to_string(workspace.frontend_path(frontend_path)).expect("Something went wrong reading the file")
 }
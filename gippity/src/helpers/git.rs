@@ -0,0 +1,137 @@
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::workspace::Workspace;
+use crate::models::agent_basic::basic_agent::AgentState;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+
+// Author used for commits made by agents
+const GIT_AUTHOR_NAME: &str = "gippity";
+const GIT_AUTHOR_EMAIL: &str = "gippity@localhost";
+
+
+// Run a git command in a folder, returning stdout
+fn run_git(dir: &Path, args: &[&str]) -> std::io::Result<String> {
+  let output: Output = Command::new("git")
+    .current_dir(dir)
+    .args(["-c", &format!("user.name={}", GIT_AUTHOR_NAME), "-c", &format!("user.email={}", GIT_AUTHOR_EMAIL)])
+    .args(args)
+    .output()?;
+  if !output.status.success() {
+    let stderr: String = String::from_utf8_lossy(&output.stderr).to_string();
+    return Err(Error::other(format!("git {}: {}", args.join(" "), stderr.trim())));
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+
+// Confirms whether the workspace is a repo of its own
+pub fn is_project_repo(workspace: &Workspace) -> bool {
+  workspace.root.join(".git").exists()
+}
+
+
+// Turns a workspace into a git repo so agent steps can be committed
+// Workspaces checked in to another repo (such as the website) are left alone, while untracked ones
+// inside another repo (such as runs in the ignored projects folder) still get a repo of their own
+// Returns whether the workspace is a project repo
+pub fn init_project_repo(workspace: &Workspace) -> std::io::Result<bool> {
+  if is_project_repo(workspace) {
+    return Ok(true);
+  }
+  let root: PathBuf = fs::canonicalize(&workspace.root)?;
+  if let Ok(toplevel) = run_git(&root, &["rev-parse", "--show-toplevel"]) {
+    if Path::new(toplevel.trim()) == root {
+      return Ok(true);
+    }
+    if !run_git(&root, &["ls-files", "--", "."])?.trim().is_empty() {
+      return Ok(false);
+    }
+  }
+  run_git(&root, &["init", "-q"])?;
+
+  // Keep run records out of project history
+  fs::write(root.join(".git").join("info").join("exclude"), ".gippity/\n")?;
+  Ok(true)
+}
+
+
+// Commits everything in the workspace, naming the agent, AI function and state
+// Returns false if not a project repo or there was nothing to commit
+pub fn commit_step(workspace: &Workspace, agent_pos: &str, ai_func_name: &str, state: &AgentState) -> std::io::Result<bool> {
+  if !is_project_repo(workspace) {
+    return Ok(false);
+  }
+  run_git(&workspace.root, &["add", "-A"])?;
+  if run_git(&workspace.root, &["status", "--porcelain"])?.trim().is_empty() {
+    return Ok(false);
+  }
+  let message: String = format!("{}: {} ({:?})", agent_pos, ai_func_name, state);
+  run_git(&workspace.root, &["commit", "-q", "-m", &message])?;
+  Ok(true)
+}
+
+
+// Commits an agent step, reporting rather than failing if git is unavailable
+pub fn record_step(workspace: &Workspace, agent_pos: &str, ai_func_name: &str, state: &AgentState) {
+  if let Err(e) = commit_step(workspace, agent_pos, ai_func_name, state) {
+    let err_msg: String = format!("Unable to commit {}: {}", ai_func_name, e);
+    PrintCommand::Issue.print_agent_message(agent_pos, err_msg.as_str());
+  }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn commits_each_agent_step() {
+    let root: std::path::PathBuf = std::env::temp_dir().join(format!("gippity-git-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let workspace: Workspace = Workspace::new(&root);
+
+    assert!(init_project_repo(&workspace).unwrap());
+    fs::create_dir_all(root.join(".gippity")).unwrap();
+    fs::write(root.join(".gippity").join("run.json"), "{}").unwrap();
+    fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+    let first: bool = commit_step(&workspace, "Backend Developer", "print_backend_webserver_code", &AgentState::Discovery).unwrap();
+    let unchanged: bool = commit_step(&workspace, "Backend Developer", "print_improved_webserver_code", &AgentState::Working).unwrap();
+    fs::write(root.join("main.rs"), "fn main() { println!(\"fixed\"); }").unwrap();
+    let fixed: bool = commit_step(&workspace, "Backend Developer", "print_fixed_code", &AgentState::UnitTesting).unwrap();
+    let log: String = run_git(&root, &["log", "--format=%s"]).unwrap();
+    let files: String = run_git(&root, &["ls-files"]).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(first);
+    assert!(!unchanged);
+    assert!(fixed);
+    assert_eq!(log, "Backend Developer: print_fixed_code (UnitTesting)\nBackend Developer: print_backend_webserver_code (Discovery)\n");
+    assert_eq!(files, "main.rs\n");
+  }
+
+  #[test]
+  fn inits_untracked_workspace_inside_another_repo() {
+    let outer: PathBuf = std::env::temp_dir().join(format!("gippity-git-outer-{}", std::process::id()));
+    let run_root: PathBuf = outer.join("projects").join("run-1");
+    let site_root: PathBuf = outer.join("website");
+    fs::create_dir_all(&run_root).unwrap();
+    fs::create_dir_all(&site_root).unwrap();
+    run_git(&outer, &["init", "-q"]).unwrap();
+    fs::write(outer.join(".gitignore"), "projects/\n").unwrap();
+    fs::write(site_root.join("index.html"), "").unwrap();
+    run_git(&outer, &["add", "-A"]).unwrap();
+
+    let is_run_repo: bool = init_project_repo(&Workspace::new(&run_root)).unwrap();
+    let is_site_repo: bool = init_project_repo(&Workspace::new(&site_root)).unwrap();
+    let has_run_git: bool = run_root.join(".git").exists();
+    fs::remove_dir_all(&outer).unwrap();
+
+    assert!(is_run_repo);
+    assert!(has_run_git);
+    assert!(!is_site_repo);
+  }
+}
@@ -1,2 +1,21 @@
+pub mod api_client;
+pub mod app_shell;
+pub mod approval;
+pub mod auth;
 pub mod command_line;
-pub mod general;
\ No newline at end of file
+pub mod dependencies;
+pub mod endpoint_tests;
+pub mod frontend_gates;
+pub mod general;
+pub mod git;
+pub mod modules;
+pub mod openapi;
+pub mod routes;
+pub mod patch;
+pub mod quality_gates;
+pub mod safety;
+pub mod sandbox;
+pub mod scaffold;
+pub mod server;
+pub mod storage;
+pub mod workspace;
\ No newline at end of file
@@ -0,0 +1,143 @@
+use crate::helpers::quality_gates::Diagnostic;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+
+
+// Files in the backend's src folder which are not part of the webserver itself
+pub const NON_SERVER_FILES: [&str; 2] = ["codetemplate.rs", "api_tests.rs"];
+
+// Key for errors which cannot be pinned on a single file
+pub const ANY_FILE: &str = "";
+
+
+// Module of the generated backend, planned before any code is written
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendModule {
+  pub name: String,
+  pub purpose: String,
+  pub signatures: Vec<String>,
+}
+
+
+// Module layout of the generated backend
+// main.rs is always generated last, so it is not part of the plan
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ModulePlan {
+  pub modules: Vec<BackendModule>,
+}
+
+impl ModulePlan {
+
+  // Confirms every module can be written to its own file and declared from main.rs
+  pub fn validate(&self) -> Result<(), String> {
+    for (i, module) in self.modules.iter().enumerate() {
+      let is_identifier: bool = module.name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && module.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+      if !is_identifier {
+        return Err(format!("module name {:?} is not a snake case identifier", module.name));
+      }
+      let file_name: String = format!("{}.rs", module.name);
+      if module.name == "main" || NON_SERVER_FILES.contains(&file_name.as_str()) {
+        return Err(format!("module name {:?} is reserved", module.name));
+      }
+      if self.modules[..i].iter().any(|other| other.name == module.name) {
+        return Err(format!("module {:?} is planned twice", module.name));
+      }
+    }
+    Ok(())
+  }
+
+  // Public items of every module, shared with each file as it is written
+  pub fn signatures(&self) -> String {
+    self.modules.iter().map(|module| {
+      let signatures: String = module.signatures.iter().map(|signature| format!("  {}\n", signature)).collect();
+      format!("mod {} ({}):\n{}", module.name, module.purpose, signatures)
+    }).collect()
+  }
+}
+
+
+// Source file of the generated backend, with a path such as "src/main.rs"
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+  pub path: String,
+  pub code: String,
+}
+
+impl SourceFile {
+
+  // Source file of a planned module
+  pub fn module_path(name: &str) -> String {
+    format!("src/{}.rs", name)
+  }
+}
+
+
+// All source files as one text, for prompts and for reading routes across modules
+pub fn combine_sources(sources: &[SourceFile]) -> String {
+  sources.iter().map(|source| format!("// File: {}\n{}\n", source.path, source.code.trim_end())).collect::<Vec<String>>().join("\n")
+}
+
+
+// Adds a module declaration to main.rs code if it is missing
+pub fn declare_module(main_code: &str, name: &str, is_test_only: bool) -> String {
+  let declaration: String = format!("mod {};", name);
+  if main_code.lines().any(|line| line.trim() == declaration || line.trim() == format!("pub {}", declaration)) {
+    return main_code.to_string();
+  }
+  match is_test_only {
+    true => format!("{}\n\n#[cfg(test)]\n{}\n", main_code.trim_end(), declaration),
+    false => format!("{}\n{}", declaration, main_code)
+  }
+}
+
+
+// Diagnostics grouped by the file they were found in, as json for the fix loop
+pub fn errors_by_file(diagnostics: &[Diagnostic]) -> BTreeMap<String, String> {
+  let mut by_file: BTreeMap<String, Vec<&Diagnostic>> = BTreeMap::new();
+  for diagnostic in diagnostics {
+    by_file.entry(diagnostic.file.clone()).or_default().push(diagnostic);
+  }
+  by_file.into_iter()
+    .map(|(file, diagnostics)| (file, serde_json::to_string_pretty(&diagnostics).expect("Failed to encode diagnostics")))
+    .collect()
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  fn module(name: &str) -> BackendModule {
+    BackendModule { name: name.to_string(), purpose: "testing".to_string(), signatures: vec!["pub struct Task".to_string()] }
+  }
+
+  #[test]
+  fn validates_module_plan() {
+    let plan: ModulePlan = serde_json::from_str("{\"modules\": [{\"name\": \"models\", \"purpose\": \"shared types\", \
+      \"signatures\": [\"pub struct Task { pub id: u64 }\"]}]}").unwrap();
+    assert_eq!(plan.validate(), Ok(()));
+    assert_eq!(plan.signatures(), "mod models (shared types):\n  pub struct Task { pub id: u64 }\n");
+
+    assert!(ModulePlan { modules: vec![module("Handlers")] }.validate().is_err());
+    assert!(ModulePlan { modules: vec![module("api_tests")] }.validate().is_err());
+    assert!(ModulePlan { modules: vec![module("db"), module("db")] }.validate().is_err());
+  }
+
+  #[test]
+  fn declares_modules_and_groups_errors() {
+    let main_code: &str = "mod models;\n\nfn main() {}\n";
+    assert_eq!(declare_module(main_code, "models", false), main_code);
+    assert_eq!(declare_module(main_code, "db", false), "mod db;\nmod models;\n\nfn main() {}\n");
+    assert_eq!(declare_module(main_code, "api_tests", true), "mod models;\n\nfn main() {}\n\n#[cfg(test)]\nmod api_tests;\n");
+
+    let diagnostic = |file: &str, line: usize| Diagnostic { file: file.to_string(), line, code: None, message: "broken".to_string() };
+    let errors: BTreeMap<String, String> = errors_by_file(&[diagnostic("src/db.rs", 3), diagnostic("src/main.rs", 1), diagnostic("src/db.rs", 9)]);
+    assert_eq!(errors.keys().collect::<Vec<&String>>(), vec!["src/db.rs", "src/main.rs"]);
+    assert!(errors["src/db.rs"].contains("\"line\": 9"));
+
+    let sources: Vec<SourceFile> = vec![SourceFile { path: SourceFile::module_path("db"), code: "pub fn load() {}\n".to_string() }];
+    assert_eq!(combine_sources(&sources), "// File: src/db.rs\npub fn load() {}\n");
+  }
+}
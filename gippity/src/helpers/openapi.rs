@@ -0,0 +1,250 @@
+use crate::models::agents::agent_traits::RouteObject;
+use serde_json::{json, Map, Value};
+
+
+// Version of the OpenAPI specification documents are written to
+const OPENAPI_VERSION: &str = "3.1.0";
+
+// Name of the security scheme protected routes require
+const BEARER_AUTH: &str = "bearerAuth";
+
+// Where component schemas are referenced from
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+
+// JSON schema of a shape such as {"id": "number", "name": "string"}, as used in api_endpoints.json
+fn shape_schema(shape: &Value) -> Value {
+  match shape {
+    Value::String(kind) => match kind.as_str() {
+      "number" => json!({"type": "number"}),
+      "string" => json!({"type": "string"}),
+      "bool" => json!({"type": "boolean"}),
+      "object" => json!({"type": "object"}),
+      "Array" => json!({"type": "array"}),
+      _ => json!({})
+    },
+    Value::Array(items) => match items.first() {
+      Some(item) => json!({"type": "array", "items": shape_schema(item)}),
+      None => json!({"type": "array"})
+    },
+    Value::Object(fields) => {
+      let properties: Map<String, Value> = fields.iter()
+        .map(|(name, field)| (name.clone(), shape_schema(field)))
+        .collect();
+      json!({"type": "object", "properties": properties, "required": fields.keys().collect::<Vec<&String>>()})
+    },
+    _ => json!({})
+  }
+}
+
+
+// Shape of a JSON schema, resolving references to component schemas
+fn schema_shape(schema: &Value, components: &Map<String, Value>) -> Value {
+  if let Some(component) = schema_ref_name(schema).and_then(|name| components.get(name)) {
+    return schema_shape(component, components);
+  }
+  match schema.get("type").and_then(Value::as_str) {
+    Some("number") | Some("integer") => json!("number"),
+    Some("string") => json!("string"),
+    Some("boolean") => json!("bool"),
+    Some("array") => match schema.get("items") {
+      Some(items) => json!([schema_shape(items, components)]),
+      None => json!("Array")
+    },
+    Some("object") => match schema.get("properties").and_then(Value::as_object) {
+      Some(properties) => Value::Object(properties.iter()
+        .map(|(name, property)| (name.clone(), schema_shape(property, components)))
+        .collect()),
+      None => json!("object")
+    },
+    _ => json!("unknown")
+  }
+}
+
+
+// Component name a schema refers to, such as Task for {"$ref": "#/components/schemas/Task"}
+fn schema_ref_name(schema: &Value) -> Option<&str> {
+  schema.get("$ref")?.as_str()?.strip_prefix(SCHEMA_REF_PREFIX)
+}
+
+
+// Schema of a request or response body, moving the struct it holds into the component schemas
+// Lists refer to their item's component, so Vec<Task> becomes an array of Task
+fn body_schema(shape: &Value, type_name: Option<&String>, schemas: &mut Map<String, Value>) -> Value {
+  match (shape, type_name) {
+    (Value::Object(_), Some(name)) => {
+      schemas.entry(name.clone()).or_insert_with(|| shape_schema(shape));
+      json!({"$ref": format!("{}{}", SCHEMA_REF_PREFIX, name)})
+    },
+    (Value::Array(items), Some(_)) if items.first().is_some_and(Value::is_object) => {
+      json!({"type": "array", "items": body_schema(&items[0], type_name, schemas)})
+    },
+    _ => shape_schema(shape)
+  }
+}
+
+
+// Names of the {param} path parameters in a route, in order
+fn route_params(route: &str) -> Vec<&str> {
+  route.split('{').skip(1).filter_map(|rest| rest.split_once('}').map(|(name, _)| name)).collect()
+}
+
+
+// OpenAPI operation for one route
+fn route_operation(route: &RouteObject, schemas: &mut Map<String, Value>) -> Value {
+  let mut operation: Map<String, Value> = Map::new();
+  if let Some(description) = &route.description {
+    operation.insert("summary".to_string(), json!(description));
+  }
+
+  // Path parameters are always required, and strings unless the handler says otherwise
+  let parameters: Vec<Value> = route_params(&route.route).into_iter().map(|name| json!({
+    "name": name,
+    "in": "path",
+    "required": true,
+    "schema": route.path_params.get(name).map(shape_schema).unwrap_or(json!({"type": "string"})),
+  })).collect();
+  if !parameters.is_empty() {
+    operation.insert("parameters".to_string(), json!(parameters));
+  }
+
+  // Request body, when the route takes one
+  if route.request_body != json!("None") {
+    let schema: Value = body_schema(&route.request_body, route.request_type.as_ref(), schemas);
+    operation.insert("requestBody".to_string(), json!({"required": true, "content": {"application/json": {"schema": schema}}}));
+  }
+
+  // Successful response, with a JSON body unless the route returns none
+  let response: Value = match &route.response {
+    Value::String(kind) if kind == "None" => json!({"description": "OK"}),
+    Value::String(kind) if kind == "not_provided" => json!({"description": "OK", "content": {"application/json": {"schema": {}}}}),
+    shape => {
+      let schema: Value = body_schema(shape, route.response_type.as_ref(), schemas);
+      json!({"description": "OK", "content": {"application/json": {"schema": schema}}})
+    }
+  };
+  let mut responses: Map<String, Value> = Map::new();
+  responses.insert("200".to_string(), response);
+  if route.is_protected {
+    responses.insert("401".to_string(), json!({"description": "Missing or invalid token"}));
+    operation.insert("security".to_string(), json!([{ BEARER_AUTH: [] }]));
+  }
+  operation.insert("responses".to_string(), Value::Object(responses));
+  Value::Object(operation)
+}
+
+
+// OpenAPI 3.1 document for every route of the generated backend
+pub fn openapi_document(description: &str, routes: &[RouteObject]) -> Value {
+  let mut paths: Map<String, Value> = Map::new();
+  let mut schemas: Map<String, Value> = Map::new();
+  for route in routes {
+    let operation: Value = route_operation(route, &mut schemas);
+    let path_item: &mut Value = paths.entry(route.route.clone()).or_insert_with(|| json!({}));
+    path_item[route.method.as_str()] = operation;
+  }
+
+  let mut components: Map<String, Value> = Map::new();
+  components.insert("schemas".to_string(), Value::Object(schemas));
+  if routes.iter().any(|route| route.is_protected) {
+    components.insert("securitySchemes".to_string(), json!({ BEARER_AUTH: {"type": "http", "scheme": "bearer", "bearerFormat": "JWT"} }));
+  }
+  json!({
+    "openapi": OPENAPI_VERSION,
+    "info": {"title": "Backend API", "version": "0.1.0", "description": description},
+    "paths": paths,
+    "components": components,
+  })
+}
+
+
+// Routes described by an OpenAPI document, for calling each endpoint
+pub fn routes_from_openapi(document: &Value) -> Result<Vec<RouteObject>, String> {
+  let paths: &Map<String, Value> = document.get("paths").and_then(Value::as_object)
+    .ok_or("document has no paths")?;
+  let empty: Map<String, Value> = Map::new();
+  let components: &Map<String, Value> = document.pointer("/components/schemas").and_then(Value::as_object).unwrap_or(&empty);
+
+  let mut routes: Vec<RouteObject> = vec![];
+  for (route, path_item) in paths {
+    let operations: &Map<String, Value> = path_item.as_object().ok_or(format!("path {} is not an object", route))?;
+    for (method, operation) in operations {
+
+      // Bodies, along with the component they refer to
+      let body: Option<&Value> = operation.pointer("/requestBody/content/application~1json/schema");
+      let response: Option<&Value> = operation.pointer("/responses/200/content/application~1json/schema");
+      let type_name = |schema: &Value| schema_ref_name(schema)
+        .or_else(|| schema.get("items").and_then(schema_ref_name))
+        .map(String::from);
+      let response_shape: Value = match response {
+        Some(schema) if schema.as_object().is_some_and(Map::is_empty) => json!("not_provided"),
+        Some(schema) => schema_shape(schema, components),
+        None => json!("None")
+      };
+
+      // Path parameters which are not plain strings keep their shape
+      let path_params: Map<String, Value> = operation.get("parameters").and_then(Value::as_array).unwrap_or(&vec![]).iter()
+        .filter(|parameter| parameter.get("in").and_then(Value::as_str) == Some("path"))
+        .filter_map(|parameter| Some((parameter.get("name")?.as_str()?.to_string(), schema_shape(parameter.get("schema")?, components))))
+        .collect();
+
+      routes.push(RouteObject {
+        is_route_dynamic: route.contains('{'),
+        method: method.clone(),
+        request_body: body.map(|schema| schema_shape(schema, components)).unwrap_or(json!("None")),
+        response: response_shape,
+        route: route.clone(),
+        description: operation.get("summary").and_then(Value::as_str).map(String::from),
+        is_protected: operation.get("security").and_then(Value::as_array).is_some_and(|security| !security.is_empty()),
+        path_params,
+        request_type: body.and_then(type_name),
+        response_type: response.and_then(type_name),
+      });
+    }
+  }
+  Ok(routes)
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::helpers::routes::extract_routes;
+
+  #[test]
+  fn writes_and_reads_back_openapi_document() {
+    let code: &str = include_str!("../template/project/backend/src/codetemplate.rs");
+    let mut routes: Vec<RouteObject> = extract_routes(code).unwrap();
+    routes[1].description = Some("Lists every task".to_string());
+    routes[1].is_protected = true;
+    let document: Value = openapi_document("Todo app", &routes);
+
+    assert_eq!(document["openapi"], json!("3.1.0"));
+    assert_eq!(document.pointer("/components/schemas/Task/required"), Some(&json!(["completed", "id", "name"])));
+    assert_eq!(document.pointer("/paths/~1task/post/requestBody/content/application~1json/schema"), Some(&json!({"$ref": "#/components/schemas/Task"})));
+    assert_eq!(document.pointer("/paths/~1task/get/responses/200/content/application~1json/schema/items"), Some(&json!({"$ref": "#/components/schemas/Task"})));
+    assert_eq!(document.pointer("/paths/~1task~1{id}/delete/parameters/0"),
+      Some(&json!({"name": "id", "in": "path", "required": true, "schema": {"type": "number"}})));
+    assert_eq!(document.pointer("/paths/~1task/get/security"), Some(&json!([{"bearerAuth": []}])));
+    assert!(document.pointer("/components/securitySchemes/bearerAuth").is_some());
+
+    // Reading the document back gives the same routes, ordered by path then method
+    // The update handler never extracts its {id}, so it reads back as a string
+    let mut read_back: Vec<RouteObject> = routes_from_openapi(&document).unwrap();
+    routes[3].path_params.insert("id".to_string(), json!("string"));
+    let key = |route: &RouteObject| (route.route.clone(), route.method.clone());
+    routes.sort_by_key(key);
+    read_back.sort_by_key(key);
+    assert_eq!(read_back, routes);
+  }
+
+  #[test]
+  fn reads_schemas_saved_with_string_flags() {
+    let saved: &str = r#"[{"is_route_dynamic": "true", "method": "get", "request_body": "None", "response": "Array", "route": "/task/{id}"}]"#;
+    let routes: Vec<RouteObject> = serde_json::from_str(saved).unwrap();
+    assert!(routes[0].is_route_dynamic);
+    assert!(!routes[0].is_protected);
+    assert!(serde_json::from_str::<Vec<RouteObject>>(&saved.replace("\"true\"", "\"yes\"")).is_err());
+  }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+
+// Markers around each search/replace edit block
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+
+// Replaces one exact snippet of code with another
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditBlock {
+  pub search: String,
+  pub replace: String,
+}
+
+
+// Reasons edits could not be applied, so the whole file is rewritten instead
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+  NoEdits,
+  Malformed(String),
+  NotFound(usize),
+  Ambiguous(usize, usize),
+}
+
+impl fmt::Display for PatchError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PatchError::NoEdits => write!(f, "no edit blocks found"),
+      PatchError::Malformed(reason) => write!(f, "malformed edit block: {}", reason),
+      PatchError::NotFound(block) => write!(f, "edit block {} does not match the code", block + 1),
+      PatchError::Ambiguous(block, count) => write!(f, "edit block {} matches the code {} times", block + 1, count),
+    }
+  }
+}
+
+
+// Reads edit blocks from a model response, ignoring anything between them
+pub fn parse_edit_blocks(text: &str) -> Result<Vec<EditBlock>, PatchError> {
+  let mut blocks: Vec<EditBlock> = vec![];
+  let mut lines = text.lines();
+  while let Some(line) = lines.next() {
+    if line.trim_end() != SEARCH_MARKER {
+      continue;
+    }
+
+    // Search lines run to the divider, replace lines to the closing marker
+    let mut search: Vec<&str> = vec![];
+    let mut replace: Vec<&str> = vec![];
+    let mut is_searching: bool = true;
+    let mut is_closed: bool = false;
+    for line in lines.by_ref() {
+      match line.trim_end() {
+        DIVIDER_MARKER if is_searching => is_searching = false,
+        REPLACE_MARKER if !is_searching => {
+          is_closed = true;
+          break;
+        },
+        SEARCH_MARKER | DIVIDER_MARKER | REPLACE_MARKER => return Err(PatchError::Malformed(format!("unexpected {:?}", line.trim_end()))),
+        _ if is_searching => search.push(line),
+        _ => replace.push(line),
+      }
+    }
+    if !is_closed {
+      return Err(PatchError::Malformed("block is not closed".to_string()));
+    }
+    if search.iter().all(|line| line.trim().is_empty()) {
+      return Err(PatchError::Malformed("search section is empty".to_string()));
+    }
+    blocks.push(EditBlock { search: search.join("\n"), replace: replace.join("\n") });
+  }
+  match blocks.is_empty() {
+    true => Err(PatchError::NoEdits),
+    false => Ok(blocks)
+  }
+}
+
+
+// Byte ranges of lines in code matching the search lines, ignoring trailing whitespace
+fn find_matches(code: &str, search: &str) -> Vec<(usize, usize)> {
+  let search_lines: Vec<&str> = search.lines().map(str::trim_end).collect();
+  if search_lines.is_empty() {
+    return vec![];
+  }
+  let mut line_starts: Vec<usize> = vec![0];
+  line_starts.extend(code.match_indices('\n').map(|(i, _)| i + 1));
+  let code_lines: Vec<&str> = code.lines().collect();
+
+  let mut matches: Vec<(usize, usize)> = vec![];
+  for start in 0..code_lines.len().saturating_sub(search_lines.len() - 1) {
+    let is_match: bool = search_lines.iter().enumerate()
+      .all(|(offset, search_line)| code_lines[start + offset].trim_end() == *search_line);
+    if is_match {
+      let last: usize = start + search_lines.len() - 1;
+      let end: usize = line_starts[last] + code_lines[last].len();
+      matches.push((line_starts[start], end));
+    }
+  }
+  matches
+}
+
+
+// Applies edit blocks in order, each of which must match exactly one place in the code
+pub fn apply_edit_blocks(code: &str, blocks: &[EditBlock]) -> Result<String, PatchError> {
+  let mut patched: String = code.to_string();
+  for (i, block) in blocks.iter().enumerate() {
+    let matches: Vec<(usize, usize)> = find_matches(&patched, &block.search);
+    match matches.as_slice() {
+      [] => return Err(PatchError::NotFound(i)),
+      [(start, end)] => patched.replace_range(start..end, &block.replace),
+      _ => return Err(PatchError::Ambiguous(i, matches.len())),
+    }
+  }
+  Ok(patched)
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  const CODE: &str = "fn main() {\n    let port = 8080;\n    println!(\"{}\", port);\n}\n\nfn helper() {\n    println!(\"{}\", port);\n}\n";
+
+  #[test]
+  fn applies_edit_blocks() {
+    let response: &str = "Fixing the port:\n```\n<<<<<<< SEARCH\n    let port = 8080;   \n=======\n    let port = 3000;\n    let host = \"127.0.0.1\";\n>>>>>>> REPLACE\n```\n<<<<<<< SEARCH\nfn helper() {\n    println!(\"{}\", port);\n}\n=======\n>>>>>>> REPLACE\n";
+    let blocks: Vec<EditBlock> = parse_edit_blocks(response).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(apply_edit_blocks(CODE, &blocks).unwrap(),
+      "fn main() {\n    let port = 3000;\n    let host = \"127.0.0.1\";\n    println!(\"{}\", port);\n}\n\n\n");
+  }
+
+  #[test]
+  fn detects_conflicts() {
+    let edit = |search: &str| vec![EditBlock { search: search.to_string(), replace: String::new() }];
+    assert_eq!(apply_edit_blocks(CODE, &edit("    let port = 9090;")), Err(PatchError::NotFound(0)));
+    assert_eq!(apply_edit_blocks(CODE, &edit("    println!(\"{}\", port);")), Err(PatchError::Ambiguous(0, 2)));
+    assert_eq!(parse_edit_blocks("just the whole file"), Err(PatchError::NoEdits));
+    assert_eq!(parse_edit_blocks("<<<<<<< SEARCH\nfn main() {\n=======\n"), Err(PatchError::Malformed("block is not closed".to_string())));
+  }
+}
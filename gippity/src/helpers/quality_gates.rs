@@ -0,0 +1,243 @@
+use crate::helpers::sandbox::SandboxCommand;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+
+// Longest raw output kept when a gate fails without any diagnostics
+const MAX_RAW_OUTPUT_LEN: usize = 2000;
+
+
+// Extra checks run on generated backend code once it builds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum QualityGate {
+  Clippy,
+  Fmt,
+  Test,
+}
+
+
+// Problem found by a gate, passed to the fix loop instead of raw stderr
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+  pub file: String,
+  pub line: usize,
+  pub code: Option<String>,
+  pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match &self.code {
+      Some(code) => write!(f, "{}:{}: [{}] {}", self.file, self.line, code, self.message),
+      None => write!(f, "{}:{}: {}", self.file, self.line, self.message),
+    }
+  }
+}
+
+
+impl QualityGate {
+
+  // Command which runs the gate on a cargo project
+  pub fn command(&self, dir: &Path) -> SandboxCommand {
+    match self {
+      QualityGate::Clippy => SandboxCommand::new("cargo", &["clippy", "--offline", "--message-format=json", "--", "-D", "warnings"], dir),
+      QualityGate::Fmt => SandboxCommand::new("cargo", &["fmt", "--check"], dir),
+      QualityGate::Test => {
+        let mut command: SandboxCommand = SandboxCommand::new("cargo", &["test", "--offline", "--message-format=json"], dir);
+        command.env.push(("RUST_BACKTRACE".to_string(), "0".to_string()));
+        command
+      }
+    }
+  }
+
+  // Problems found by a finished gate command, empty when it passed
+  pub fn diagnostics(&self, output: &Output, dir: &Path) -> Vec<Diagnostic> {
+    if output.status.success() {
+      return vec![];
+    }
+    let stdout: String = String::from_utf8_lossy(&output.stdout).to_string();
+    let diagnostics: Vec<Diagnostic> = match self {
+      QualityGate::Clippy => parse_compiler_messages(&stdout),
+      QualityGate::Fmt => parse_fmt_diffs(&stdout, dir),
+      QualityGate::Test => {
+        let mut diagnostics: Vec<Diagnostic> = parse_compiler_messages(&stdout);
+        diagnostics.extend(parse_test_failures(&stdout));
+        diagnostics
+      }
+    };
+
+    // Still report a failing gate when its output could not be understood
+    if !diagnostics.is_empty() {
+      return diagnostics;
+    }
+    let stderr: String = String::from_utf8_lossy(&output.stderr).to_string();
+    let skip: usize = stderr.chars().count().saturating_sub(MAX_RAW_OUTPUT_LEN);
+    vec![Diagnostic {
+      file: String::new(),
+      line: 0,
+      code: Some(format!("{:?}", self).to_lowercase()),
+      message: stderr.chars().skip(skip).collect(),
+    }]
+  }
+}
+
+
+// Errors from cargo's --message-format=json output
+pub fn parse_compiler_messages(stdout: &str) -> Vec<Diagnostic> {
+  let mut diagnostics: Vec<Diagnostic> = vec![];
+  for line in stdout.lines().filter(|line| line.starts_with('{')) {
+    let Ok(json) = serde_json::from_str::<Value>(line) else {
+      continue;
+    };
+    if json["reason"] != "compiler-message" || json["message"]["level"] != "error" {
+      continue;
+    }
+    let message: String = json["message"]["message"].as_str().unwrap_or_default().to_string();
+    if message.starts_with("aborting due to") {
+      continue;
+    }
+    let primary_span: Option<&Value> = json["message"]["spans"].as_array()
+      .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true));
+    let diagnostic: Diagnostic = Diagnostic {
+      file: primary_span.and_then(|span| span["file_name"].as_str()).unwrap_or_default().to_string(),
+      line: primary_span.and_then(|span| span["line_start"].as_u64()).unwrap_or_default() as usize,
+      code: json["message"]["code"]["code"].as_str().map(String::from),
+      message,
+    };
+    if !diagnostics.contains(&diagnostic) {
+      diagnostics.push(diagnostic);
+    }
+  }
+  diagnostics
+}
+
+
+// Unformatted code from "Diff in <file>:<line>:" lines of cargo fmt --check
+// rustfmt gives absolute paths, so they are made relative to the folder as the filesystem resolves it
+pub fn parse_fmt_diffs(stdout: &str, dir: &Path) -> Vec<Diagnostic> {
+  let dir: PathBuf = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+  stdout.lines()
+    .filter_map(|line| line.strip_prefix("Diff in ")?.strip_suffix(':'))
+    .filter_map(|location| {
+      let (file, line) = location.rsplit_once(':').or_else(|| location.rsplit_once(" at line "))?;
+      Some(Diagnostic {
+        file: Path::new(file).strip_prefix(&dir).map(|file| file.to_string_lossy().to_string()).unwrap_or(file.to_string()),
+        line: line.parse().ok()?,
+        code: Some("rustfmt".to_string()),
+        message: "code is not formatted, run cargo fmt".to_string(),
+      })
+    })
+    .collect()
+}
+
+
+// Failing tests and where they panicked from cargo test output
+pub fn parse_test_failures(stdout: &str) -> Vec<Diagnostic> {
+  let mut diagnostics: Vec<Diagnostic> = vec![];
+  let mut current: Option<Diagnostic> = None;
+  let mut is_reading_message: bool = false;
+  for line in stdout.lines() {
+
+    // Each failing test's output starts with "---- <name> stdout ----"
+    if let Some(name) = line.strip_prefix("---- ").and_then(|rest| rest.strip_suffix(" stdout ----")) {
+      diagnostics.extend(current.take());
+      current = Some(Diagnostic { file: String::new(), line: 0, code: Some("test_failed".to_string()), message: format!("test {} failed", name) });
+      is_reading_message = false;
+      continue;
+    }
+    let Some(diagnostic) = current.as_mut() else {
+      continue;
+    };
+    if line == "failures:" {
+      diagnostics.extend(current.take());
+      continue;
+    }
+
+    // "thread '<name>' panicked at src/main.rs:10:5:" followed by the panic message
+    if let Some(location) = line.split(" panicked at ").nth(1) {
+      let mut parts = location.split(':');
+      diagnostic.file = parts.next().unwrap_or_default().to_string();
+      diagnostic.line = parts.next().and_then(|line| line.parse().ok()).unwrap_or_default();
+      is_reading_message = true;
+      continue;
+    }
+    if is_reading_message {
+      if line.is_empty() || line.starts_with("note:") || line.starts_with("stack backtrace:") {
+        is_reading_message = false;
+      } else {
+        diagnostic.message.push_str(&format!("\n{}", line));
+      }
+    }
+  }
+  diagnostics.extend(current);
+  diagnostics
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_clippy_and_fmt_output() {
+    let stdout: &str = concat!(
+      r#"{"reason":"compiler-artifact","package_id":"serde"}"#, "\n",
+      r#"{"reason":"compiler-message","message":{"level":"error","message":"unused variable: `x`","code":{"code":"unused_variables","explanation":null},"spans":[{"file_name":"src/main.rs","is_primary":true,"line_start":182}]}}"#, "\n",
+      r#"{"reason":"compiler-message","message":{"level":"warning","message":"field is never read","code":null,"spans":[]}}"#, "\n",
+      r#"{"reason":"compiler-message","message":{"level":"error","message":"aborting due to 1 previous error","code":null,"spans":[]}}"#, "\n",
+    );
+    assert_eq!(parse_compiler_messages(stdout), vec![Diagnostic {
+      file: "src/main.rs".to_string(),
+      line: 182,
+      code: Some("unused_variables".to_string()),
+      message: "unused variable: `x`".to_string(),
+    }]);
+
+    let fmt_stdout: &str = "Diff in /tmp/backend/src/main.rs:1:\n use actix_cors::Cors;\n-use std::sync::Mutex;\nDiff in /tmp/backend/src/main.rs at line 40:\n";
+    let diagnostics: Vec<Diagnostic> = parse_fmt_diffs(fmt_stdout, Path::new("/tmp/backend"));
+    assert_eq!(diagnostics.iter().map(|d| d.to_string()).collect::<Vec<String>>(), vec![
+      "src/main.rs:1: [rustfmt] code is not formatted, run cargo fmt",
+      "src/main.rs:40: [rustfmt] code is not formatted, run cargo fmt",
+    ]);
+
+    // A relative backend folder still matches rustfmt's absolute paths
+    let relative_dir: &Path = Path::new("src/template/project/backend");
+    let relative_stdout: String = format!("Diff in {}:3:\n", std::fs::canonicalize(relative_dir).unwrap().join("src").join("main.rs").display());
+    assert_eq!(parse_fmt_diffs(&relative_stdout, relative_dir)[0].file, "src/main.rs");
+  }
+
+  #[test]
+  fn parses_test_failures() {
+    let stdout: &str = "running 2 tests
+test tests::adds ... ok
+test tests::fails ... FAILED
+
+failures:
+
+---- tests::fails stdout ----
+
+thread 'tests::fails' (21417) panicked at src/main.rs:187:9:
+assertion `left == right` failed: math broke
+  left: 2
+ right: 3
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::fails
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.02s
+";
+    assert_eq!(parse_test_failures(stdout), vec![Diagnostic {
+      file: "src/main.rs".to_string(),
+      line: 187,
+      code: Some("test_failed".to_string()),
+      message: "test tests::fails failed\nassertion `left == right` failed: math broke\n  left: 2\n right: 3".to_string(),
+    }]);
+  }
+}
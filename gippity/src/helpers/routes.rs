@@ -0,0 +1,622 @@
+use crate::helpers::auth::AUTH_GUARD;
+use crate::models::agents::agent_traits::RouteObject;
+use serde_json::{json, Map, Value};
+use syn::visit::{self, Visit};
+use std::collections::HashMap;
+
+
+// Actix methods which can be routed to
+const HTTP_METHODS: [&str; 6] = ["get", "post", "put", "patch", "delete", "head"];
+
+// How deep to follow nested structs and variable bindings
+const MAX_DEPTH: usize = 8;
+
+
+// Everything in the backend the extractor needs to look up by name
+#[derive(Default)]
+struct CodeIndex {
+  structs: HashMap<String, Vec<(String, syn::Type)>>,
+  fn_returns: HashMap<String, syn::Type>,
+  handlers: HashMap<String, syn::ItemFn>,
+}
+
+impl CodeIndex {
+  fn new(file: &syn::File) -> Self {
+    let mut index: CodeIndex = CodeIndex::default();
+    for item in &file.items {
+      match item {
+        syn::Item::Struct(item_struct) => {
+          let fields: Vec<(String, syn::Type)> = item_struct.fields.iter()
+            .filter_map(|field| field.ident.as_ref().map(|ident| (ident.to_string(), field.ty.clone())))
+            .collect();
+          index.structs.insert(item_struct.ident.to_string(), fields);
+        },
+        syn::Item::Fn(item_fn) => {
+          if let syn::ReturnType::Type(_, ty) = &item_fn.sig.output {
+            index.fn_returns.insert(item_fn.sig.ident.to_string(), (**ty).clone());
+          }
+          index.handlers.insert(item_fn.sig.ident.to_string(), item_fn.clone());
+        },
+        syn::Item::Impl(item_impl) => {
+          for impl_item in &item_impl.items {
+            if let syn::ImplItem::Fn(method) = impl_item {
+              if let syn::ReturnType::Type(_, ty) = &method.sig.output {
+                index.fn_returns.insert(method.sig.ident.to_string(), (**ty).clone());
+              }
+            }
+          }
+        },
+        _ => {}
+      }
+    }
+    index
+  }
+
+  // JSON shape of a rust type, in the same style as api_endpoints.json
+  fn type_schema(&self, ty: &syn::Type, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+      return json!("unknown");
+    }
+    match ty {
+      syn::Type::Reference(reference) => self.type_schema(&reference.elem, depth),
+      syn::Type::Paren(paren) => self.type_schema(&paren.elem, depth),
+      syn::Type::Slice(slice) => json!([self.type_schema(&slice.elem, depth + 1)]),
+      syn::Type::Array(array) => json!([self.type_schema(&array.elem, depth + 1)]),
+      syn::Type::Path(type_path) => {
+        let Some(segment) = type_path.path.segments.last() else {
+          return json!("unknown");
+        };
+        let name: String = segment.ident.to_string();
+        match name.as_str() {
+          "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "f32" | "f64" => json!("number"),
+          "String" | "str" | "char" | "Uuid" | "DateTime" | "NaiveDateTime" | "NaiveDate" => json!("string"),
+          "bool" => json!("bool"),
+          "HashMap" | "BTreeMap" | "Value" => json!("object"),
+          "Option" | "Box" | "Json" | "Form" | "Data" | "Mutex" | "Arc" => match first_generic(segment) {
+            Some(inner) => self.type_schema(inner, depth),
+            None => json!("unknown")
+          },
+          "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => match first_generic(segment) {
+            Some(inner) => json!([self.type_schema(inner, depth + 1)]),
+            None => json!("Array")
+          },
+          _ => match self.structs.get(&name) {
+            Some(fields) => {
+              let mut object: Map<String, Value> = Map::new();
+              for (field_name, field_ty) in fields {
+                object.insert(field_name.clone(), self.type_schema(field_ty, depth + 1));
+              }
+              Value::Object(object)
+            },
+            None => json!("unknown")
+          }
+        }
+      },
+      _ => json!("unknown")
+    }
+  }
+
+  // Name of the struct a type holds, such as Task for web::Json<Vec<Task>>
+  fn type_name(&self, ty: &syn::Type, depth: usize) -> Option<String> {
+    if depth > MAX_DEPTH {
+      return None;
+    }
+    match ty {
+      syn::Type::Reference(reference) => self.type_name(&reference.elem, depth + 1),
+      syn::Type::Paren(paren) => self.type_name(&paren.elem, depth + 1),
+      syn::Type::Slice(slice) => self.type_name(&slice.elem, depth + 1),
+      syn::Type::Array(array) => self.type_name(&array.elem, depth + 1),
+      syn::Type::Path(type_path) => {
+        let segment: &syn::PathSegment = type_path.path.segments.last()?;
+        let name: String = segment.ident.to_string();
+        match self.structs.contains_key(&name) {
+          true => Some(name),
+          false => first_generic(segment).and_then(|inner| self.type_name(inner, depth + 1))
+        }
+      },
+      _ => None
+    }
+  }
+}
+
+
+// First generic argument, such as T in Json<T>
+fn first_generic(segment: &syn::PathSegment) -> Option<&syn::Type> {
+  match &segment.arguments {
+    syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+      syn::GenericArgument::Type(ty) => Some(ty),
+      _ => None
+    }),
+    _ => None
+  }
+}
+
+
+// Last segment of a path, such as "get" in web::get
+fn last_ident(path: &syn::Path) -> String {
+  path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default()
+}
+
+
+// Strips Option, Result and references to get at the value a binding holds
+fn unwrap_type(ty: &syn::Type) -> syn::Type {
+  match ty {
+    syn::Type::Reference(reference) => unwrap_type(&reference.elem),
+    syn::Type::Path(type_path) => {
+      let segment: Option<&syn::PathSegment> = type_path.path.segments.last();
+      match segment.map(|segment| (segment.ident.to_string(), first_generic(segment))) {
+        Some((name, Some(inner))) if name == "Option" || name == "Result" => unwrap_type(inner),
+        _ => ty.clone()
+      }
+    },
+    _ => ty.clone()
+  }
+}
+
+
+// Method and handler name from a route target such as web::get().to(handler)
+fn route_target(expr: &syn::Expr) -> Option<(String, String)> {
+  let syn::Expr::MethodCall(to_call) = expr else {
+    return None;
+  };
+  if to_call.method != "to" {
+    return None;
+  }
+  let syn::Expr::Call(method_call) = &*to_call.receiver else {
+    return None;
+  };
+  let syn::Expr::Path(method_path) = &*method_call.func else {
+    return None;
+  };
+  let method: String = last_ident(&method_path.path);
+  let Some(syn::Expr::Path(handler_path)) = to_call.args.first() else {
+    return None;
+  };
+  match HTTP_METHODS.contains(&method.as_str()) {
+    true => Some((method, last_ident(&handler_path.path))),
+    false => None
+  }
+}
+
+
+// String argument of a call such as web::resource("/item"), if the call is to one of the given names
+fn literal_call_arg(expr: &syn::Expr, names: &[&str]) -> Option<String> {
+  let syn::Expr::Call(call) = expr else {
+    return None;
+  };
+  let syn::Expr::Path(func) = &*call.func else {
+    return None;
+  };
+  match (names.contains(&last_ident(&func.path).as_str()), call.args.first()) {
+    (true, Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }))) => Some(lit.value()),
+    _ => None
+  }
+}
+
+
+// Walks back along a builder chain looking for a web::resource or web::scope path
+fn chain_prefix(mut expr: &syn::Expr) -> Option<String> {
+  loop {
+    if let Some(path) = literal_call_arg(expr, &["resource", "scope"]) {
+      return Some(path);
+    }
+    match expr {
+      syn::Expr::MethodCall(call) => expr = &call.receiver,
+      _ => return None
+    }
+  }
+}
+
+
+// Finds whether an expression names a function, such as require_auth in from_fn(require_auth)
+struct NameFinder<'a> {
+  name: &'a str,
+  is_found: bool,
+}
+
+impl<'ast> Visit<'ast> for NameFinder<'_> {
+  fn visit_path(&mut self, path: &'ast syn::Path) {
+    self.is_found |= last_ident(path) == self.name;
+    visit::visit_path(self, path);
+  }
+}
+
+
+// Walks back along a builder chain looking for a .wrap(...) of the auth guard
+fn chain_is_protected(mut expr: &syn::Expr) -> bool {
+  loop {
+    match expr {
+      syn::Expr::MethodCall(call) => {
+        if call.method == "wrap" {
+          let mut finder: NameFinder = NameFinder { name: AUTH_GUARD, is_found: false };
+          call.args.iter().for_each(|arg| finder.visit_expr(arg));
+          if finder.is_found {
+            return true;
+          }
+        }
+        expr = &call.receiver;
+      },
+      _ => return false
+    }
+  }
+}
+
+
+// A route registration found in the code
+struct RegisteredRoute {
+  route: String,
+  method: String,
+  handler: String,
+  is_protected: bool,
+}
+
+
+// Collects every route registration
+#[derive(Default)]
+struct RouteVisitor {
+  routes: Vec<RegisteredRoute>,
+}
+
+impl<'ast> Visit<'ast> for RouteVisitor {
+  fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+    visit::visit_expr_method_call(self, call);
+    if call.method != "route" {
+      return;
+    }
+    let args: Vec<&syn::Expr> = call.args.iter().collect();
+    let prefix: String = chain_prefix(&call.receiver).unwrap_or_default();
+    let registration: Option<(String, &syn::Expr)> = match args.as_slice() {
+      [syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }), target] => Some((format!("{}{}", prefix, lit.value()), *target)),
+      [target] if !prefix.is_empty() => Some((prefix, *target)),
+      _ => None
+    };
+    if let Some((route, target)) = registration {
+      if let Some((method, handler)) = route_target(target) {
+        let is_protected: bool = chain_is_protected(&call.receiver);
+        self.routes.push(RegisteredRoute { route, method, handler, is_protected });
+      }
+    }
+  }
+}
+
+
+// Local variables in a handler and what they were bound from
+#[derive(Default)]
+struct BindingVisitor {
+  typed: HashMap<String, syn::Type>,
+  assigned: HashMap<String, syn::Expr>,
+  unwrapped: HashMap<String, syn::Expr>,
+  json_args: Vec<syn::Expr>,
+}
+
+impl BindingVisitor {
+
+  // Records `Some(x)` / `Ok(x)` patterns as unwrapping the given expression
+  fn bind_unwrapped(&mut self, pat: &syn::Pat, expr: &syn::Expr) {
+    if let syn::Pat::TupleStruct(tuple) = pat {
+      let name: String = last_ident(&tuple.path);
+      if let (true, Some(syn::Pat::Ident(ident))) = (name == "Some" || name == "Ok", tuple.elems.first()) {
+        self.unwrapped.insert(ident.ident.to_string(), expr.clone());
+      }
+    }
+  }
+}
+
+impl<'ast> Visit<'ast> for BindingVisitor {
+  fn visit_local(&mut self, local: &'ast syn::Local) {
+    let init: Option<&syn::Expr> = local.init.as_ref().map(|init| &*init.expr);
+    match (&local.pat, init) {
+      (syn::Pat::Type(pat_type), _) => {
+        if let syn::Pat::Ident(ident) = &*pat_type.pat {
+          self.typed.insert(ident.ident.to_string(), (*pat_type.ty).clone());
+        }
+      },
+      (syn::Pat::Ident(ident), Some(expr)) => {
+        self.assigned.insert(ident.ident.to_string(), expr.clone());
+      },
+      (pat, Some(expr)) => self.bind_unwrapped(pat, expr),
+      _ => {}
+    }
+    visit::visit_local(self, local);
+  }
+
+  fn visit_expr_match(&mut self, expr_match: &'ast syn::ExprMatch) {
+    for arm in &expr_match.arms {
+      self.bind_unwrapped(&arm.pat, &expr_match.expr);
+    }
+    visit::visit_expr_match(self, expr_match);
+  }
+
+  fn visit_expr_let(&mut self, expr_let: &'ast syn::ExprLet) {
+    self.bind_unwrapped(&expr_let.pat, &expr_let.expr);
+    visit::visit_expr_let(self, expr_let);
+  }
+
+  fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+    if call.method == "json" {
+      if let Some(arg) = call.args.first() {
+        self.json_args.push(arg.clone());
+      }
+    }
+    visit::visit_expr_method_call(self, call);
+  }
+}
+
+
+// Works out the type of an expression in a handler as far as it can
+fn expr_type(expr: &syn::Expr, index: &CodeIndex, bindings: &BindingVisitor, depth: usize) -> Option<syn::Type> {
+  if depth > MAX_DEPTH {
+    return None;
+  }
+  match expr {
+    syn::Expr::Reference(reference) => expr_type(&reference.expr, index, bindings, depth + 1),
+    syn::Expr::Paren(paren) => expr_type(&paren.expr, index, bindings, depth + 1),
+    syn::Expr::Unary(unary) => expr_type(&unary.expr, index, bindings, depth + 1),
+    syn::Expr::Struct(expr_struct) => Some(syn::Type::Path(syn::TypePath { qself: None, path: expr_struct.path.clone() })),
+    syn::Expr::MethodCall(call) => match call.method.to_string().as_str() {
+      "clone" | "to_owned" | "into_inner" | "lock" | "borrow" => expr_type(&call.receiver, index, bindings, depth + 1),
+      "unwrap" | "expect" | "unwrap_or_default" => expr_type(&call.receiver, index, bindings, depth + 1).map(|ty| unwrap_type(&ty)),
+      method => index.fn_returns.get(method).cloned()
+    },
+    syn::Expr::Call(call) => match &*call.func {
+      syn::Expr::Path(func) => index.fn_returns.get(&last_ident(&func.path)).cloned(),
+      _ => None
+    },
+    syn::Expr::Path(path) => {
+      let name: String = last_ident(&path.path);
+      if let Some(ty) = bindings.typed.get(&name) {
+        return Some(ty.clone());
+      }
+      if let Some(assigned) = bindings.assigned.get(&name) {
+        return expr_type(assigned, index, bindings, depth + 1);
+      }
+      bindings.unwrapped.get(&name)
+        .and_then(|unwrapped| expr_type(unwrapped, index, bindings, depth + 1))
+        .map(|ty| unwrap_type(&ty))
+    },
+    _ => None
+  }
+}
+
+
+// Handler argument using one of the given extractors, such as web::Json<Task>
+fn extractor_arg<'a>(handler: &'a syn::ItemFn, names: &[&str]) -> Option<&'a syn::Type> {
+  handler.sig.inputs.iter().find_map(|input| match input {
+    syn::FnArg::Typed(pat_type) => match &*pat_type.ty {
+      syn::Type::Path(type_path) if names.contains(&last_ident(&type_path.path).as_str()) => Some(&*pat_type.ty),
+      _ => None
+    },
+    _ => None
+  })
+}
+
+
+// Shape of a request or response body, and the name of the struct it holds if known
+type Body = (Value, Option<String>);
+
+
+// Request body from a Json or Form extractor in the handler's arguments
+fn request_body(handler: &syn::ItemFn, index: &CodeIndex) -> Body {
+  match extractor_arg(handler, &["Json", "Form"]) {
+    Some(ty) => (index.type_schema(ty, 0), index.type_name(ty, 0)),
+    None => (json!("None"), None)
+  }
+}
+
+
+// Path parameters of a route and their shapes, from a Path extractor in the handler's arguments
+// Parameters the handler does not extract are left out
+fn path_params(handler: &syn::ItemFn, route: &str, index: &CodeIndex) -> Map<String, Value> {
+  let names: Vec<String> = route.split('{').skip(1)
+    .filter_map(|rest| rest.split_once('}').map(|(name, _)| name.to_string()))
+    .collect();
+  let mut params: Map<String, Value> = Map::new();
+  let Some(syn::Type::Path(type_path)) = extractor_arg(handler, &["Path"]) else {
+    return params;
+  };
+  let Some(inner) = type_path.path.segments.last().and_then(first_generic) else {
+    return params;
+  };
+  match inner {
+    syn::Type::Tuple(tuple) => {
+      for (name, elem) in names.iter().zip(tuple.elems.iter()) {
+        params.insert(name.clone(), index.type_schema(elem, 0));
+      }
+    },
+    _ => match index.type_schema(inner, 0) {
+      Value::Object(fields) => {
+        for name in &names {
+          if let Some(field) = fields.get(name) {
+            params.insert(name.clone(), field.clone());
+          }
+        }
+      },
+      shape => {
+        if let Some(name) = names.first() {
+          params.insert(name.clone(), shape);
+        }
+      }
+    }
+  }
+  params
+}
+
+
+// Response body from a Json return type or whatever the handler passes to .json(...)
+fn response_body(handler: &syn::ItemFn, index: &CodeIndex) -> Body {
+  if let syn::ReturnType::Type(_, ty) = &handler.sig.output {
+    let inner: syn::Type = unwrap_type(ty);
+    if let syn::Type::Path(type_path) = &inner {
+      if last_ident(&type_path.path) == "Json" {
+        return (index.type_schema(&inner, 0), index.type_name(&inner, 0));
+      }
+    }
+  }
+
+  let mut bindings: BindingVisitor = BindingVisitor::default();
+  for input in &handler.sig.inputs {
+    if let syn::FnArg::Typed(pat_type) = input {
+      if let syn::Pat::Ident(ident) = &*pat_type.pat {
+        bindings.typed.insert(ident.ident.to_string(), (*pat_type.ty).clone());
+      }
+    }
+  }
+  bindings.visit_block(&handler.block);
+  if bindings.json_args.is_empty() {
+    return (json!("None"), None);
+  }
+  match bindings.json_args.iter().find_map(|arg| expr_type(arg, index, &bindings, 0)) {
+    Some(ty) => (index.type_schema(&ty, 0), index.type_name(&ty, 0)),
+    None => (json!("not_provided"), None)
+  }
+}
+
+
+// Routes registered with #[get("/path")] style attributes
+// Whether these are behind the auth guard depends on where the service is mounted, so they are left unprotected
+fn attribute_routes(index: &CodeIndex) -> Vec<RegisteredRoute> {
+  let mut routes: Vec<(String, String, String)> = vec![];
+  for (name, handler) in &index.handlers {
+    for attr in &handler.attrs {
+      let method: String = last_ident(attr.path());
+      if HTTP_METHODS.contains(&method.as_str()) {
+        if let Ok(lit) = attr.parse_args::<syn::LitStr>() {
+          routes.push((lit.value(), method, name.clone()));
+        }
+      }
+    }
+  }
+  routes.sort();
+  routes.into_iter()
+    .map(|(route, method, handler)| RegisteredRoute { route, method, handler, is_protected: false })
+    .collect()
+}
+
+
+// Parses generated actix code and lists every route with its method and request and response shapes
+// Descriptions are left for the LLM to fill in
+pub fn extract_routes(code: &str) -> syn::Result<Vec<RouteObject>> {
+  let file: syn::File = syn::parse_file(code)?;
+  let index: CodeIndex = CodeIndex::new(&file);
+  let mut visitor: RouteVisitor = RouteVisitor::default();
+  visitor.visit_file(&file);
+  visitor.routes.extend(attribute_routes(&index));
+
+  Ok(visitor.routes.into_iter().map(|RegisteredRoute { route, method, handler, is_protected }| {
+    let ((request_body, request_type), (response, response_type), path_params): (Body, Body, Map<String, Value>) = match index.handlers.get(&handler) {
+      Some(handler) => (request_body(handler, &index), response_body(handler, &index), path_params(handler, &route, &index)),
+      None => ((json!("None"), None), (json!("not_provided"), None), Map::new())
+    };
+    RouteObject {
+      is_route_dynamic: route.contains('{'),
+      method,
+      request_body,
+      response,
+      route,
+      description: None,
+      is_protected,
+      path_params,
+      request_type,
+      response_type,
+    }
+  }).collect())
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn extracts_code_template_routes() {
+    let code: &str = include_str!("../template/project/backend/src/codetemplate.rs");
+    let routes: Vec<RouteObject> = extract_routes(code).unwrap();
+    let task: Value = json!({"id": "number", "name": "string", "completed": "bool"});
+    let user: Value = json!({"id": "number", "username": "string", "password": "string"});
+
+    let summary: Vec<(&str, &str, bool)> = routes.iter()
+      .map(|route| (route.route.as_str(), route.method.as_str(), route.is_route_dynamic)).collect();
+    assert_eq!(summary, vec![
+      ("/task", "post", false),
+      ("/task", "get", false),
+      ("/task/{id}", "get", true),
+      ("/task/{id}", "put", true),
+      ("/task/{id}", "delete", true),
+      ("/register", "post", false),
+      ("/login", "post", false),
+    ]);
+    assert_eq!(routes[0].request_body, task);
+    assert_eq!(routes[0].request_type.as_deref(), Some("Task"));
+    assert_eq!(routes[0].response, json!("None"));
+    assert_eq!(routes[1].response, json!([task.clone()]));
+    assert_eq!(routes[1].response_type.as_deref(), Some("Task"));
+    assert_eq!(routes[2].response, task);
+    assert_eq!(routes[2].request_body, json!("None"));
+    assert_eq!(routes[2].path_params, json!({"id": "number"}).as_object().cloned().unwrap());
+    assert_eq!(routes[6].request_body, user);
+  }
+
+  #[test]
+  fn extracts_resources_scopes_and_attributes() {
+    let code: &str = r#"
+      struct Quote { symbol: String, price: f64, tags: Option<Vec<String>> }
+
+      #[get("/quotes/{symbol}")]
+      async fn quote(symbol: web::Path<String>) -> Result<web::Json<Quote>, Error> { todo!() }
+
+      async fn list() -> impl Responder {
+        let quotes: Vec<Quote> = vec![];
+        HttpResponse::Ok().json(&quotes)
+      }
+
+      async fn unknown() -> impl Responder {
+        HttpResponse::Ok().json(serde_json::json!({"ok": true}))
+      }
+
+      fn main() {
+        App::new()
+          .service(web::resource("/quotes").route(web::get().to(list)))
+          .service(web::scope("/api").route("/status", web::get().to(unknown)))
+          .service(quote);
+      }
+    "#;
+    let routes: Vec<RouteObject> = extract_routes(code).unwrap();
+    let quote: Value = json!({"symbol": "string", "price": "number", "tags": ["string"]});
+
+    assert_eq!(routes.len(), 3);
+    assert_eq!((routes[0].route.as_str(), &routes[0].response), ("/quotes", &json!([quote.clone()])));
+    assert_eq!((routes[1].route.as_str(), &routes[1].response), ("/api/status", &json!("not_provided")));
+    assert_eq!((routes[2].route.as_str(), routes[2].is_route_dynamic), ("/quotes/{symbol}", true));
+    assert_eq!(routes[2].response, quote);
+    assert_eq!(routes[2].path_params, json!({"symbol": "string"}).as_object().cloned().unwrap());
+    assert_eq!(routes[2].response_type.as_deref(), Some("Quote"));
+  }
+  #[test]
+  fn marks_routes_behind_auth_guard() {
+    let code: &str = r#"
+      fn main() {
+        App::new()
+          .wrap(Cors::permissive())
+          .route("/register", web::post().to(register))
+          .route("/login", web::post().to(login))
+          .service(
+            web::scope("")
+              .wrap(from_fn(auth::require_auth))
+              .route("/logout", web::post().to(logout))
+              .route("/task", web::get().to(read_all_tasks))
+          )
+          .service(web::scope("/admin").wrap(Logger::default()).route("/stats", web::get().to(stats)));
+      }
+    "#;
+    let routes: Vec<(String, bool)> = extract_routes(code).unwrap().into_iter()
+      .map(|route| (route.route, route.is_protected)).collect();
+    assert_eq!(routes, vec![
+      ("/register".to_string(), false),
+      ("/login".to_string(), false),
+      ("/logout".to_string(), true),
+      ("/task".to_string(), true),
+      ("/admin/stats".to_string(), false),
+    ]);
+  }
+}
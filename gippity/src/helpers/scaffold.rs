@@ -0,0 +1,102 @@
+use crate::helpers::workspace::Workspace;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+
+// Starting backend, copied into every new project
+const BACKEND_TEMPLATE: &[(&str, &str)] = &[
+  ("Cargo.toml", include_str!("../template/project/backend/Cargo.toml")),
+  ("build.rs", include_str!("../template/project/backend/build.rs")),
+  (".gitignore", include_str!("../template/project/backend/.gitignore")),
+  ("src/main.rs", include_str!("../template/project/backend/src/main.rs")),
+  ("src/codetemplate.rs", include_str!("../template/project/backend/src/codetemplate.rs")),
+];
+
+
+// Starting Vite, React and Tailwind frontend, copied into every new project
+// Placeholder components are included so the frontend builds before agents have written anything
+const FRONTEND_TEMPLATE: &[(&str, &str)] = &[
+  ("package.json", include_str!("../template/project/frontend/package.json")),
+  ("yarn.lock", include_str!("../template/project/frontend/yarn.lock")),
+  (".gitignore", include_str!("../template/project/frontend/.gitignore")),
+  (".eslintrc.cjs", include_str!("../template/project/frontend/.eslintrc.cjs")),
+  ("index.html", include_str!("../template/project/frontend/index.html")),
+  ("vite.config.ts", include_str!("../template/project/frontend/vite.config.ts")),
+  ("tsconfig.json", include_str!("../template/project/frontend/tsconfig.json")),
+  ("tsconfig.node.json", include_str!("../template/project/frontend/tsconfig.node.json")),
+  ("tailwind.config.js", include_str!("../template/project/frontend/tailwind.config.js")),
+  ("postcss.config.js", include_str!("../template/project/frontend/postcss.config.js")),
+  ("public/vite.svg", include_str!("../template/project/frontend/public/vite.svg")),
+  ("src/main.tsx", include_str!("../template/project/frontend/src/main.tsx")),
+  ("src/App.tsx", include_str!("../template/project/frontend/src/App.tsx")),
+  ("src/App.css", include_str!("../template/project/frontend/src/App.css")),
+  ("src/index.css", include_str!("../template/project/frontend/src/index.css")),
+  ("src/vite-env.d.ts", include_str!("../template/project/frontend/src/vite-env.d.ts")),
+  ("src/routes.ts", include_str!("../template/project/frontend/src/routes.ts")),
+  ("src/api/client.ts", include_str!("../template/project/frontend/src/api/client.ts")),
+  ("src/api/types.ts", include_str!("../template/project/frontend/src/api/types.ts")),
+  ("src/components/Controller.tsx", include_str!("../template/project/frontend/src/components/Controller.tsx")),
+  ("src/components/shared/Logo.tsx", include_str!("../template/project/frontend/src/components/shared/Logo.tsx")),
+  ("src/components/shared/Navigation.tsx", include_str!("../template/project/frontend/src/components/shared/Navigation.tsx")),
+  ("src/components/shared/Footer.tsx", include_str!("../template/project/frontend/src/components/shared/Footer.tsx")),
+  ("src/components/pages/PageOne.tsx", include_str!("../template/project/frontend/src/components/pages/PageOne.tsx")),
+  ("src/components/pages/PageTwo.tsx", include_str!("../template/project/frontend/src/components/pages/PageTwo.tsx")),
+  ("src/components/pages/NotFound.tsx", include_str!("../template/project/frontend/src/components/pages/NotFound.tsx")),
+];
+
+
+// Writes template files into a folder, leaving any file which already exists untouched
+fn write_template(dir: &Path, template: &[(&str, &str)]) -> std::io::Result<Vec<PathBuf>> {
+  let mut written: Vec<PathBuf> = vec![];
+  for (relative_path, contents) in template {
+    let path: PathBuf = dir.join(relative_path);
+    if path.exists() {
+      continue;
+    }
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, contents)?;
+    written.push(path);
+  }
+  Ok(written)
+}
+
+
+// Copies the backend and frontend templates into a workspace so agents have a standalone project to work in
+// Safe to call on an existing project as only missing files are written
+pub fn scaffold_project(workspace: &Workspace) -> std::io::Result<Vec<PathBuf>> {
+  let mut written: Vec<PathBuf> = write_template(&workspace.backend_dir, BACKEND_TEMPLATE)?;
+  written.extend(write_template(&workspace.frontend_dir, FRONTEND_TEMPLATE)?);
+  Ok(written)
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn scaffolds_standalone_project() {
+    let root: PathBuf = std::env::temp_dir().join(format!("gippity-scaffold-{}", std::process::id()));
+    let workspace: Workspace = Workspace::new(&root);
+
+    // Existing code is kept
+    fs::create_dir_all(workspace.backend_dir.join("src")).unwrap();
+    fs::write(workspace.backend_main_path(), "fn main() {}").unwrap();
+
+    let written: Vec<PathBuf> = scaffold_project(&workspace).unwrap();
+    let main_contents: String = fs::read_to_string(workspace.backend_main_path()).unwrap();
+    let has_template: bool = workspace.code_template_path().exists();
+    let has_controller: bool = workspace.frontend_path("/src/components/Controller.tsx").exists();
+    let rewritten: Vec<PathBuf> = scaffold_project(&workspace).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(written.len(), BACKEND_TEMPLATE.len() + FRONTEND_TEMPLATE.len() - 1);
+    assert_eq!(main_contents, "fn main() {}");
+    assert!(has_template);
+    assert!(has_controller);
+    assert!(rewritten.is_empty());
+  }
+}
@@ -0,0 +1,100 @@
+use crate::helpers::sandbox::{NetworkAccess, Sandbox, SandboxCommand, SandboxedChild};
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::time;
+
+
+// How often to check whether the server is listening
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+
+// Asks the OS for a port nothing is listening on
+pub fn free_port() -> io::Result<u16> {
+  let listener: TcpListener = TcpListener::bind("127.0.0.1:0")?;
+  Ok(listener.local_addr()?.port())
+}
+
+
+// Web server started for testing on its own port, passed in through the PORT env var
+// The whole process group is killed when dropped, including when a test panics
+#[derive(Debug)]
+pub struct ServerSupervisor {
+  pub port: u16,
+  child: SandboxedChild,
+}
+
+impl ServerSupervisor {
+
+  // Start a server inside a sandbox, printing its logs under a label as they arrive
+  pub fn start(sandbox: &dyn Sandbox, mut command: SandboxCommand, log_label: &str) -> io::Result<Self> {
+    let port: u16 = free_port()?;
+    command.env.push(("PORT".to_string(), port.to_string()));
+    command.network = NetworkAccess::Allowed;
+    command.log_label = Some(log_label.to_string());
+    let child: SandboxedChild = SandboxedChild::spawn(sandbox, &command)?;
+    Ok(Self { port, child })
+  }
+
+  // Base url to call the server on
+  pub fn url(&self) -> String {
+    format!("http://localhost:{}", self.port)
+  }
+
+  // Waits until the server accepts connections, failing early if it exits
+  pub async fn wait_until_ready(&mut self, timeout: Duration) -> io::Result<()> {
+    let address: SocketAddr = SocketAddr::from(([127, 0, 0, 1], self.port));
+    let started: Instant = Instant::now();
+    loop {
+      if TcpStream::connect(address).await.is_ok() {
+        return Ok(());
+      }
+      if self.child.has_exited() {
+        return Err(io::Error::other("Server exited before it started listening"));
+      }
+      if started.elapsed() > timeout {
+        return Err(io::Error::other(format!("Server was not listening on port {} after {:?}", self.port, timeout)));
+      }
+      time::sleep(READY_POLL_INTERVAL).await;
+    }
+  }
+
+  // Stop the server, returning everything it wrote to stderr
+  pub fn stop(&mut self) -> String {
+    self.child.kill_and_read_stderr()
+  }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::helpers::sandbox::detect_sandbox;
+  use std::path::PathBuf;
+  use std::sync::Arc;
+
+  #[tokio::test]
+  async fn waits_for_server_on_its_port() {
+    let dir: PathBuf = std::env::temp_dir();
+    let sandbox: Arc<dyn Sandbox> = detect_sandbox(true).unwrap();
+
+    // Listens on the port it was given
+    let command: SandboxCommand = SandboxCommand::new("python3", &["-c", "import os, socket, time; \
+      s = socket.socket(); s.bind(('127.0.0.1', int(os.environ['PORT']))); s.listen(); time.sleep(30)"], &dir);
+    let mut server: ServerSupervisor = ServerSupervisor::start(sandbox.as_ref(), command, "Test Server").unwrap();
+    assert!(server.url().ends_with(&server.port.to_string()));
+    server.wait_until_ready(Duration::from_secs(10)).await.unwrap();
+    server.stop();
+
+    // Crashes on start
+    let command: SandboxCommand = SandboxCommand::new("sh", &["-c", "echo \"cannot bind $PORT\" >&2; exit 1"], &dir);
+    let mut server: ServerSupervisor = ServerSupervisor::start(sandbox.as_ref(), command, "Test Server").unwrap();
+    let port: u16 = server.port;
+    let started: Instant = Instant::now();
+    assert!(server.wait_until_ready(Duration::from_secs(10)).await.is_err());
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(server.stop(), format!("cannot bind {}\n", port));
+  }
+}
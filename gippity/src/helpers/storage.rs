@@ -0,0 +1,107 @@
+use crate::helpers::dependencies::{AllowedCrate, CRATE_ALLOWLIST};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+
+// Build script which embeds the migrations folder into the server, so it never reads files by a runtime path
+pub const MIGRATIONS_BUILD_SCRIPT: &str = include_str!("../template/project/backend/build.rs");
+
+
+// Where the generated backend keeps its data, each with its own code template and migrations
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageProfile {
+  #[default]
+  Json,
+  Sqlite,
+  Sled,
+}
+
+impl StorageProfile {
+
+  // Code template the backend is written from
+  pub fn code_template(&self) -> &'static str {
+    match self {
+      StorageProfile::Json => include_str!("../template/project/backend/src/codetemplate.rs"),
+      StorageProfile::Sqlite => include_str!("../template/storage/sqlite/codetemplate.rs"),
+      StorageProfile::Sled => include_str!("../template/storage/sled/codetemplate.rs"),
+    }
+  }
+
+  // Starting migrations, as file names in the migrations folder and their contents
+  pub fn migrations(&self) -> &'static [(&'static str, &'static str)] {
+    match self {
+      StorageProfile::Json => &[("0001_init.json", include_str!("../template/storage/json/migrations/0001_init.json"))],
+      StorageProfile::Sqlite => &[("0001_init.sql", include_str!("../template/storage/sqlite/migrations/0001_init.sql"))],
+      StorageProfile::Sled => &[("0001_init.json", include_str!("../template/storage/sled/migrations/0001_init.json"))],
+    }
+  }
+
+  // File extension of migrations the code template applies
+  pub fn migration_extension(&self) -> &'static str {
+    match self {
+      StorageProfile::Sqlite => "sql",
+      StorageProfile::Json | StorageProfile::Sled => "json",
+    }
+  }
+
+  // Crates the code template needs on top of the backend template
+  pub fn crates(&self) -> Vec<AllowedCrate> {
+    let names: &[&str] = match self {
+      StorageProfile::Json => &[],
+      StorageProfile::Sqlite => &["rusqlite"],
+      StorageProfile::Sled => &["sled"],
+    };
+    CRATE_ALLOWLIST.iter().filter(|allowed| names.contains(&allowed.name)).copied().collect()
+  }
+
+  // How data and migrations work, shown to the model
+  pub fn description(&self) -> &'static str {
+    match self {
+      StorageProfile::Json => "JSON file: all data is held in a Mutex<Database> and written to database.json after every change. \
+        Migrations are JSON files mapping collection names to seed records by key, merged in when missing.",
+      StorageProfile::Sqlite => "Embedded SQLite via rusqlite: data is kept in database.db behind a Mutex<Connection>. \
+        Migrations are SQL files applied once each in file name order, so new tables and columns are added with a new migration.",
+      StorageProfile::Sled => "Embedded sled database: data is kept in database.sled as trees of JSON values keyed by string. \
+        Migrations are JSON files mapping tree names to seed values by key, added when missing.",
+    }
+  }
+
+  // Checks a migration can be applied by the code template before it is saved
+  pub fn validate_migration(&self, migration: &str) -> Result<(), String> {
+    if migration.trim().is_empty() {
+      return Err("migration is empty".to_string());
+    }
+    match self {
+      StorageProfile::Sqlite => Ok(()),
+      StorageProfile::Json | StorageProfile::Sled => match serde_json::from_str::<Value>(migration) {
+        Ok(Value::Object(collections)) if collections.values().all(Value::is_object) => Ok(()),
+        Ok(_) => Err("migration must map each name to an object of records".to_string()),
+        Err(e) => Err(e.to_string())
+      }
+    }
+  }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn describes_each_profile() {
+    for profile in [StorageProfile::Json, StorageProfile::Sqlite, StorageProfile::Sled] {
+      assert!(profile.code_template().contains("fn run_migrations"));
+      assert!(profile.migrations().iter().all(|(name, _)| name.ends_with(profile.migration_extension())));
+      assert!(profile.migrations().iter().all(|(_, migration)| profile.validate_migration(migration).is_ok()));
+    }
+    assert_eq!(StorageProfile::Sqlite.crates().iter().map(|allowed| allowed.name).collect::<Vec<&str>>(), vec!["rusqlite"]);
+    assert!(StorageProfile::Json.crates().is_empty());
+    assert_eq!(serde_json::from_str::<StorageProfile>("\"sled\"").unwrap(), StorageProfile::Sled);
+
+    assert!(StorageProfile::Sled.validate_migration("{\"tasks\": {\"1\": {\"name\": \"seed\"}}}").is_ok());
+    assert!(StorageProfile::Json.validate_migration("{\"tasks\": []}").is_err());
+    assert!(StorageProfile::Sqlite.validate_migration("  ").is_err());
+  }
+}
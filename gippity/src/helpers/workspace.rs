@@ -0,0 +1,242 @@
+use serde::{Serialize, Deserialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+
+// Optional config file read from the current directory
+pub const WORKSPACE_CONFIG_FILE: &str = "gippity.json";
+
+
+// Folder new projects are scaffolded into when no workspace is given
+pub const DEFAULT_PROJECTS_DIR: &str = "projects";
+
+
+// Workspace locations which can come from the CLI, env or config file
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct WorkspaceConfig {
+  pub workspace: Option<PathBuf>,
+  pub backend_dir: Option<PathBuf>,
+  pub frontend_dir: Option<PathBuf>,
+  pub projects_dir: Option<PathBuf>,
+  pub vendor_dir: Option<PathBuf>,
+}
+
+impl WorkspaceConfig {
+
+  // Read GIPPITY_WORKSPACE, GIPPITY_BACKEND_DIR, GIPPITY_FRONTEND_DIR, GIPPITY_PROJECTS_DIR and GIPPITY_VENDOR_DIR
+  pub fn from_env() -> Self {
+    Self {
+      workspace: env::var("GIPPITY_WORKSPACE").ok().map(PathBuf::from),
+      backend_dir: env::var("GIPPITY_BACKEND_DIR").ok().map(PathBuf::from),
+      frontend_dir: env::var("GIPPITY_FRONTEND_DIR").ok().map(PathBuf::from),
+      projects_dir: env::var("GIPPITY_PROJECTS_DIR").ok().map(PathBuf::from),
+      vendor_dir: env::var("GIPPITY_VENDOR_DIR").ok().map(PathBuf::from),
+    }
+  }
+
+  // Merge with priority: CLI flag, then env, then config file
+  pub fn resolve(cli_root: Option<PathBuf>) -> Self {
+    let cli_config: WorkspaceConfig = WorkspaceConfig { workspace: cli_root, ..Default::default() };
+    cli_config
+      .or(WorkspaceConfig::from_env())
+      .or(WorkspaceConfig::from_file(Path::new(WORKSPACE_CONFIG_FILE)))
+  }
+
+  // Read config file if there is one
+  pub fn from_file(path: &Path) -> Self {
+    match fs::read_to_string(path) {
+      Ok(contents) => serde_json::from_str(&contents).expect("Failed to decode workspace config file"),
+      Err(_) => Self::default()
+    }
+  }
+
+  // Fill in anything missing from a lower priority config
+  pub fn or(self, other: WorkspaceConfig) -> Self {
+    Self {
+      workspace: self.workspace.or(other.workspace),
+      backend_dir: self.backend_dir.or(other.backend_dir),
+      frontend_dir: self.frontend_dir.or(other.frontend_dir),
+      projects_dir: self.projects_dir.or(other.projects_dir),
+      vendor_dir: self.vendor_dir.or(other.vendor_dir),
+    }
+  }
+
+  // Confirms whether an existing workspace was asked for rather than a new project
+  pub fn has_workspace(&self) -> bool {
+    self.workspace.is_some() || self.backend_dir.is_some() || self.frontend_dir.is_some()
+  }
+
+  // Folder holding one project per run
+  pub fn projects_dir(&self) -> PathBuf {
+    self.projects_dir.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_PROJECTS_DIR))
+  }
+}
+
+
+// Where agents read and write project code
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Workspace {
+  pub root: PathBuf,
+  pub backend_dir: PathBuf,
+  pub frontend_dir: PathBuf,
+}
+
+impl Workspace {
+
+  // Backend and frontend folders inside a project root
+  pub fn new(root: &Path) -> Self {
+    Self {
+      root: root.to_path_buf(),
+      backend_dir: root.join("backend"),
+      frontend_dir: root.join("frontend"),
+    }
+  }
+
+  // Build from config, with explicit backend and frontend folders taking priority over the root
+  // Falls back to the website folder checked in next to this crate
+  pub fn from_config(config: WorkspaceConfig) -> Self {
+    let root: PathBuf = config.workspace
+      .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("website"));
+    let workspace: Workspace = Self::new(&root);
+    Self {
+      backend_dir: config.backend_dir.unwrap_or(workspace.backend_dir),
+      frontend_dir: config.frontend_dir.unwrap_or(workspace.frontend_dir),
+      root,
+    }
+  }
+
+  // Throwaway project in the temp folder, so tests never write into checked-in code
+  #[cfg(test)]
+  pub fn temporary(label: &str) -> Self {
+    Self::new(&env::temp_dir().join(format!("gippity-{}-{}", label, std::process::id())))
+  }
+
+  // Project of its own inside the projects folder, named after the run
+  pub fn for_run(projects_dir: &Path, run_id: &str) -> Self {
+    Self::new(&projects_dir.join(run_id))
+  }
+
+  // Same workspace with absolute paths as the filesystem resolves them, matching the paths tools report errors with
+  // The folders must exist, so this is done once the project is scaffolded
+  pub fn canonicalize(&self) -> std::io::Result<Self> {
+    Ok(Self {
+      root: fs::canonicalize(&self.root)?,
+      backend_dir: fs::canonicalize(&self.backend_dir)?,
+      frontend_dir: fs::canonicalize(&self.frontend_dir)?,
+    })
+  }
+
+  // Backend cargo manifest
+  pub fn backend_manifest_path(&self) -> PathBuf {
+    self.backend_dir.join("Cargo.toml")
+  }
+
+  // Backend cargo config, used to point crates.io at a vendored registry
+  pub fn backend_cargo_config_path(&self) -> PathBuf {
+    self.backend_dir.join(".cargo").join("config.toml")
+  }
+
+  // Backend webserver code
+  pub fn backend_main_path(&self) -> PathBuf {
+    self.backend_dir.join("src").join("main.rs")
+  }
+
+  // Backend source file from a path such as "src/handlers.rs"
+  pub fn backend_source_path(&self, source_path: &str) -> PathBuf {
+    self.backend_dir.join(source_path)
+  }
+
+  // Module layout planned for the backend
+  pub fn module_plan_path(&self) -> PathBuf {
+    self.backend_dir.join("module_plan.json")
+  }
+
+  // Backend build script, which embeds the storage migrations
+  pub fn backend_build_script_path(&self) -> PathBuf {
+    self.backend_dir.join("build.rs")
+  }
+
+  // Backend storage migrations, applied by the server at startup
+  pub fn migrations_dir(&self) -> PathBuf {
+    self.backend_dir.join("migrations")
+  }
+
+  // Backend integration tests, a child module of the webserver code
+  pub fn backend_tests_path(&self) -> PathBuf {
+    self.backend_dir.join("src").join("api_tests.rs")
+  }
+
+  // Backend code template
+  pub fn code_template_path(&self) -> PathBuf {
+    self.backend_dir.join("src").join("codetemplate.rs")
+  }
+
+  // Backend webserver binary built by cargo
+  pub fn backend_binary_path(&self) -> PathBuf {
+    self.backend_dir.join("target").join("debug").join("backend")
+  }
+
+  // Backend json api endpoint schema
+  pub fn api_endpoints_path(&self) -> PathBuf {
+    self.backend_dir.join("api_endpoints.json")
+  }
+
+  // Backend OpenAPI document, next to the endpoint schema
+  pub fn openapi_spec_path(&self) -> PathBuf {
+    self.backend_dir.join("openapi.json")
+  }
+
+  // Frontend file from a path such as "/src/hooks/useCall.tsx"
+  pub fn frontend_path(&self, frontend_path: &str) -> PathBuf {
+    self.frontend_dir.join(frontend_path.trim_start_matches('/'))
+  }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn resolves_workspace_by_priority() {
+    let cli: WorkspaceConfig = WorkspaceConfig { workspace: Some(PathBuf::from("/tmp/cli")), ..Default::default() };
+    let env: WorkspaceConfig = WorkspaceConfig {
+      workspace: Some(PathBuf::from("/tmp/env")),
+      frontend_dir: Some(PathBuf::from("/tmp/env/web")),
+      ..Default::default()
+    };
+    let config_file: WorkspaceConfig = serde_json::from_str("{\"backend_dir\": \"/tmp/file/api\"}").unwrap();
+
+    let workspace: Workspace = Workspace::from_config(cli.or(env).or(config_file));
+    assert_eq!(workspace.root, PathBuf::from("/tmp/cli"));
+    assert_eq!(workspace.backend_dir, PathBuf::from("/tmp/file/api"));
+    assert_eq!(workspace.frontend_dir, PathBuf::from("/tmp/env/web"));
+    assert_eq!(workspace.frontend_path("/src/hooks/useCall.tsx"), PathBuf::from("/tmp/env/web/src/hooks/useCall.tsx"));
+  }
+
+  #[test]
+  fn gives_each_run_its_own_project() {
+    let config: WorkspaceConfig = WorkspaceConfig::default();
+    assert!(!config.has_workspace());
+    let first: Workspace = Workspace::for_run(&config.projects_dir(), "run-100");
+    let second: Workspace = Workspace::for_run(&config.projects_dir(), "run-101");
+    assert_eq!(first.backend_dir, PathBuf::from("projects/run-100/backend"));
+    assert_ne!(first.frontend_dir, second.frontend_dir);
+  }
+
+  #[test]
+  fn defaults_to_checked_in_website() {
+    let workspace: Workspace = Workspace::from_config(WorkspaceConfig::default());
+    assert!(workspace.code_template_path().exists());
+  }
+
+  #[test]
+  fn resolves_folders_to_absolute_paths() {
+    let workspace: Workspace = Workspace::from_config(WorkspaceConfig::default()).canonicalize().unwrap();
+    assert!(workspace.root.is_absolute());
+    assert!(!workspace.root.components().any(|component| component == std::path::Component::ParentDir));
+    assert_eq!(workspace.backend_dir, workspace.root.join("backend"));
+  }
+}
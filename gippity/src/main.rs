@@ -11,19 +11,209 @@ mod apis;
 mod helpers;
 mod ai_functions;
 
-use helpers::command_line::get_user_response;
-use models::agents_manager::managing_agent::ManagingAgent;
+use apis::call_request::configure_llm;
+use clap::{Parser, Subcommand};
+use helpers::command_line::{get_user_response, print_run_summary};
+use models::agents_manager::managing_agent::{ManagingAgent, ProjectStage, RunSettings};
+use models::agents_manager::run_record::RunRecord;
+use helpers::workspace::{Workspace, WorkspaceConfig};
+use helpers::sandbox::detect_sandbox;
+use helpers::approval::ApprovalPolicy;
+use helpers::quality_gates::QualityGate;
+use helpers::storage::StorageProfile;
+use models::agents::agent_frontend_comp::ComponentSelection;
+use std::fs;
+use std::path::PathBuf;
 
 
-#[tokio::main]
-async fn main() {
+#[derive(Parser, Debug)]
+#[command(name = "gippity", about = "Builds full stack websites with a team of AI agents")]
+struct Cli {
+
+  /// Existing project folder holding backend/ and frontend/ (else GIPPITY_WORKSPACE or gippity.json)
+  /// When not set, each run gets a new project scaffolded inside the projects folder
+  #[arg(long, global = true)]
+  workspace: Option<PathBuf>,
+
+  /// Model to use (defaults to OPEN_AI_MODEL or gpt-4)
+  #[arg(long, global = true)]
+  model: Option<String>,
+
+  /// Maximum number of calls to the model for this run
+  #[arg(long, global = true)]
+  budget: Option<u32>,
+
+  /// How AI written code is approved before it runs (defaults to interactive, or the saved run's policy)
+  #[arg(long, value_enum, global = true)]
+  approval: Option<ApprovalPolicy>,
+
+  /// Approve AI written code without asking, same as --approval auto-approve
+  #[arg(long, short = 'y', global = true, conflicts_with = "approval")]
+  yes: bool,
+
+  /// Extra checks the generated backend must pass once it builds (defaults to none, or the saved run's gates)
+  #[arg(long = "gate", value_enum, value_delimiter = ',', global = true)]
+  gates: Vec<QualityGate>,
+
+  /// Where the generated backend stores its data (defaults to the project scope's choice, or the saved run's storage)
+  #[arg(long, value_enum, global = true)]
+  storage: Option<StorageProfile>,
+
+  /// Frontend components to build, by name (e.g. HomePage) or kind (Logo, NavHeader, NavFooter, ApiClient, PageContent, AppShell)
+  #[arg(long, value_delimiter = ',', global = true)]
+  only: Vec<String>,
+
+  /// Frontend components to leave as they are, by name or kind
+  #[arg(long, value_delimiter = ',', global = true)]
+  skip: Vec<String>,
+
+  /// Run AI written code on the host with only resource limits when bubblewrap is not available
+  #[arg(long, global = true)]
+  allow_unsandboxed: bool,
+
+  #[command(subcommand)]
+  command: Command,
+}
+
+
+#[derive(Subcommand, Debug)]
+enum Command {
+
+  /// Build a website end to end
+  Build { goal: Option<String> },
+
+  /// Continue a run, skipping agents which already completed
+  Resume { run: Option<String> },
+
+  /// Scope out a project with the Solutions Architect only
+  Scope { goal: Option<String> },
+
+  /// Run the Backend Developer on a saved run
+  Backend { run: Option<String> },
+
+  /// Run the Brand Designer and Frontend Developer on a saved run
+  Frontend { run: Option<String> },
+
+  /// Show the factsheet and agent outcomes of a saved run
+  Inspect { run: String },
+}
 
-    // Obtain user goal
-    let usr_req: String = get_user_response("What are we building today?");
 
-    // Create Gippity Managing Agent
-    let managing_agent: ManagingAgent = ManagingAgent::new(usr_req).await.expect("Error creating agent");
+// Approval policy picked on the command line, if any
+fn approval_policy(cli: &Cli) -> Option<ApprovalPolicy> {
+  match cli.yes {
+    true => Some(ApprovalPolicy::AutoApprove),
+    false => cli.approval
+  }
+}
+
+
+// Frontend components picked on the command line, every component when none are
+fn component_selection(cli: &Cli) -> ComponentSelection {
+  ComponentSelection { only: cli.only.clone(), skip: cli.skip.clone() }
+}
+
+
+// Start a new run from a user goal
+async fn start_run(cli: &Cli, goal: Option<String>, stage: ProjectStage) {
+  configure_llm(cli.model.clone(), cli.budget);
 
-    dbg!(managing_agent);
+  // New runs get their own project unless a workspace was given
+  let config: WorkspaceConfig = WorkspaceConfig::resolve(cli.workspace.clone());
+  let run_id: String = RunRecord::new_run_id();
+  let vendor_dir: Option<PathBuf> = config.vendor_dir.clone();
+  let workspace: Workspace = match config.has_workspace() {
+    true => Workspace::from_config(config),
+    false => Workspace::for_run(&config.projects_dir(), &run_id)
+  };
+  let settings: RunSettings = RunSettings {
+    run_id,
+    workspace,
+    sandbox: detect_sandbox(cli.allow_unsandboxed).expect("No sandbox for AI written code"),
+    approval: approval_policy(cli).unwrap_or_default(),
+    gates: cli.gates.clone(),
+    vendor_dir,
+    storage: cli.storage,
+    components: component_selection(cli)
+  };
+
+  // Obtain user goal if not passed in
+  let usr_req: String = goal.unwrap_or_else(|| get_user_response("What are we building today?"));
+
+  // Create Gippity Managing Agent
+  let mut managing_agent: ManagingAgent = ManagingAgent::new(usr_req, settings).await
+    .expect("Error creating agent");
+  println!("Run: {}", managing_agent.run_id());
+  managing_agent.execute_project(stage).await;
+}
+
+
+// Find the workspace of a given run or the latest one
+// Runs live in their own project inside the projects folder unless a workspace was given
+fn find_run_workspace(cli: &Cli, run: &Option<String>) -> Workspace {
+  let config: WorkspaceConfig = WorkspaceConfig::resolve(cli.workspace.clone());
+  if config.has_workspace() {
+    return Workspace::from_config(config);
+  }
+  let projects_dir: PathBuf = config.projects_dir();
+  let run_id: String = match run {
+    Some(run_id) => run_id.clone(),
+    None => fs::read_dir(&projects_dir).expect("Unable to read projects folder")
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| entry.path().is_dir())
+      .filter_map(|entry| entry.file_name().into_string().ok())
+      .max()
+      .expect("No projects found")
+  };
+  Workspace::for_run(&projects_dir, &run_id)
+}
 
-}
\ No newline at end of file
+
+// Load a given run or the latest one
+fn load_run(workspace: &Workspace, run: Option<String>) -> RunRecord {
+  let run_res: std::io::Result<RunRecord> = match run {
+    Some(run_id) => RunRecord::load(&workspace.root, &run_id),
+    None => RunRecord::load_latest(&workspace.root),
+  };
+  run_res.expect("Unable to load run")
+}
+
+
+// Pick up a saved run, keeping its model, budget, approval policy, gates and storage unless overridden
+fn continue_run(cli: &Cli, run: Option<String>) -> ManagingAgent {
+  let workspace: Workspace = find_run_workspace(cli, &run);
+  let run_record: RunRecord = load_run(&workspace, run);
+  configure_llm(cli.model.clone().or(Some(run_record.model.clone())), cli.budget.or(run_record.budget));
+  let settings: RunSettings = RunSettings {
+    run_id: run_record.run_id.clone(),
+    workspace,
+    sandbox: detect_sandbox(cli.allow_unsandboxed).expect("No sandbox for AI written code"),
+    approval: approval_policy(cli).unwrap_or(run_record.approval_policy),
+    gates: match cli.gates.is_empty() {
+      true => run_record.gates.clone(),
+      false => cli.gates.clone()
+    },
+    vendor_dir: WorkspaceConfig::resolve(cli.workspace.clone()).vendor_dir,
+    storage: cli.storage.or(run_record.storage),
+    components: component_selection(cli)
+  };
+  ManagingAgent::from_run(run_record, settings)
+}
+
+
+#[tokio::main]
+async fn main() {
+  let cli: Cli = Cli::parse();
+
+  match &cli.command {
+    Command::Build { goal } => start_run(&cli, goal.clone(), ProjectStage::Full).await,
+    Command::Scope { goal } => start_run(&cli, goal.clone(), ProjectStage::Scope).await,
+    Command::Resume { run } => continue_run(&cli, run.clone()).resume_project().await,
+    Command::Backend { run } => continue_run(&cli, run.clone()).execute_project(ProjectStage::Backend).await,
+    Command::Frontend { run } => continue_run(&cli, run.clone()).execute_project(ProjectStage::Frontend).await,
+    Command::Inspect { run } => {
+      let run: Option<String> = Some(run.clone());
+      print_run_summary(&load_run(&find_run_workspace(&cli, &run), run))
+    },
+  }
+}
@@ -1,5 +1,4 @@
 use crate::models::agent_basic::basic_traits::BasicTraits;
-use crate::models::general::llm::Message;
 
 
 #[derive(Debug, PartialEq)]
@@ -13,41 +12,14 @@ pub enum AgentState {
 
 #[derive(Debug)]
 pub struct BasicAgent {
-  pub objective: String,
   pub position: String,
-  pub state: AgentState,
-  pub memory: Vec<Message>
+  pub state: AgentState
 }
 
 impl BasicTraits for BasicAgent {
 
-  fn new(objective: String, position: String) -> Self {
-    Self {
-      objective,
-      position,
-      state: AgentState::Discovery,
-      memory: Vec::from([])
-    }
-  }
-
   fn update_state(&mut self, new_state: AgentState) {
     self.state = new_state;
   }
 
-  fn get_objective(&self) -> &String {
-    &self.objective
-  }
-
-  fn get_position(&self) -> &String {
-    &self.position
-  }
-
-  fn get_state(&self) -> &AgentState {
-    &self.state
-  }
-
-  fn get_memory(&self) -> &Vec<Message> {
-    &self.memory
-  }
-
 }
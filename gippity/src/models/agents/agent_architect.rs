@@ -1,6 +1,6 @@
 use crate::models::agent_basic::basic_agent::{BasicAgent, AgentState};
 use crate::models::agent_basic::basic_traits::BasicTraits;
-use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet, ProjectScope};
+use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet, FactSheetField, ProjectScope};
 use crate::ai_functions::aifunc_architect::{print_project_scope, print_site_urls};
 use crate::helpers::command_line::PrintCommand;
 use crate::helpers::general::{ai_task_request_decoded, check_status_code};
@@ -20,10 +20,8 @@ impl AgentSolutionArchitect {
 
     // Define attributes
     let attributes: BasicAgent = BasicAgent {
-      objective: "Gathers information and design solutions for website builds".to_string(),
       position: "Solutions Architect".to_string(),
-      state: AgentState::Discovery,
-      memory: vec![]
+      state: AgentState::Discovery
     };
 
     // Return Self
@@ -43,9 +41,9 @@ impl AgentSolutionArchitect {
       print_project_scope).await;
 
     // Update state and return Project Scope
-    factsheet.project_scope = Some(ai_response.clone());
+    factsheet.project_scope = Some(ai_response);
     self.attributes.update_state(AgentState::Finished);
-    return ai_response;
+    ai_response
   }
 
   // AI Call: Retrieve external urls
@@ -71,8 +69,18 @@ impl SpecialFunctions for AgentSolutionArchitect {
     &self.attributes
   }
 
+  // Only needs the project description to get going
+  fn reads(&self) -> Vec<FactSheetField> {
+    vec![FactSheetField::ProjectDescription]
+  }
+
+  // Scopes out the project and any external urls
+  fn produces(&self) -> Vec<FactSheetField> {
+    vec![FactSheetField::ProjectScope, FactSheetField::ExternalUrls]
+  }
+
   // Execute main functions
-  async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error>> {
+  async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error + Send>> {
 
     // Continue until finished
     // !!! WARNING - If this loop runs without a stop, you can incur infinite costs with OpenAI !!!
@@ -126,9 +134,9 @@ impl SpecialFunctions for AgentSolutionArchitect {
           }
         
           // Exclude any faulty URLs
-          if exclude_urls.len() > 0 {
+          if !exclude_urls.is_empty() {
             let new_urls: Vec<String> = factsheet.external_urls.as_ref().unwrap()
-              .iter().filter(|url| !exclude_urls.contains(&url)).cloned().collect();
+              .iter().filter(|url| !exclude_urls.contains(url)).cloned().collect();
             factsheet.external_urls = Some(new_urls);
           }
 
@@ -163,12 +171,13 @@ pub mod tests {
       project_scope: None,
       external_urls: None,
       backend_code: None,
-      api_endpoint_schema: None
+      api_endpoint_schema: None,
+      brand_colours: None
     };
 
     // Execute running agent
     agent.execute(&mut factsheet).await.expect("Unable to execute running agent");
-    assert!(factsheet.project_scope != None);
+    assert!(factsheet.project_scope.is_some());
     assert!(factsheet.external_urls.is_some());
   }
 }
@@ -1,61 +1,165 @@
 use crate::ai_functions::aifunc_backend::{
   print_backend_webserver_code, 
+  print_backend_crates,
+  print_storage_migration,
   print_improved_webserver_code, 
   print_fixed_code,
-  print_rest_api_endpoints
+  print_fixed_code_edits,
+  print_backend_module_plan,
+  print_backend_module_code,
+  print_improved_webserver_code_edits,
+  print_rest_api_endpoint_descriptions,
+  print_backend_integration_tests
 };
 use crate::helpers::general::{
-  check_status_code, 
   read_code_template_contents, 
   save_backend_code,
+  read_backend_sources,
+  save_backend_source,
+  save_module_plan,
+  save_backend_dependencies,
+  save_vendor_config,
+  save_storage_template,
+  read_migrations,
+  save_migration,
+  read_api_endpoints,
   save_api_endpoints,
-  BACKEND_CODE_DIR
+  save_openapi_spec,
+  read_backend_tests,
+  save_backend_tests,
+  declare_backend_tests
 };
-use crate::helpers::command_line::confirm_safe_code;
+use crate::helpers::workspace::Workspace;
+use crate::helpers::git::record_step;
+use crate::helpers::approval::Approver;
+use crate::helpers::routes::extract_routes;
+use crate::helpers::openapi::{openapi_document, routes_from_openapi};
+use crate::helpers::endpoint_tests::{run_endpoint_tests, EndpointReport};
+use crate::helpers::safety::{hosts_from_urls, SafetyReport};
 use crate::models::agent_basic::basic_agent::{BasicAgent, AgentState};
 use crate::helpers::command_line::PrintCommand;
-use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet, RouteObject};
+use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet, FactSheetField, RouteObject};
 use crate::helpers::general::ai_task_request;
+use crate::helpers::sandbox::{run_sandboxed, Sandbox, SandboxCommand};
+use crate::helpers::server::ServerSupervisor;
+use crate::helpers::quality_gates::{parse_compiler_messages, Diagnostic, QualityGate};
+use crate::helpers::patch::{apply_edit_blocks, parse_edit_blocks, PatchError};
+use crate::helpers::modules::{combine_sources, declare_module, errors_by_file, ModulePlan, SourceFile, ANY_FILE};
+use crate::helpers::dependencies::{allowlist_description, select_crates, AllowedCrate};
+use crate::helpers::storage::StorageProfile;
+use crate::helpers::auth::{auth_crates, with_auth_module, AUTH_MODULE_CODE, AUTH_MODULE_NAME, AUTH_USAGE};
 use async_trait::async_trait;
 
-use std::fs;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::time;
 use reqwest::Client;
+use serde_json::Value;
+
+// Most server log kept when passing runtime failures back for fixing
+const MAX_SERVER_LOG_LEN: usize = 2000;
+
+// How long the backend server has to start listening
+const SERVER_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Integration tests, as failures in them are keyed
+const TESTS_SOURCE_PATH: &str = "src/api_tests.rs";
+
+// End of a server log, short enough to pass back for fixing
+fn log_tail(log: &str) -> String {
+  let skip: usize = log.chars().count().saturating_sub(MAX_SERVER_LOG_LEN);
+  log.chars().skip(skip).collect()
+}
+
+// Labels each file's errors with where they came from
+fn labelled(label: &str, errors: BTreeMap<String, String>) -> BTreeMap<String, String> {
+  errors.into_iter().map(|(file, errors)| (file, format!("{}: {}", label, errors))).collect()
+}
+
+// Cargo's home folder, where downloaded crates are kept
+fn cargo_home() -> PathBuf {
+  match std::env::var("CARGO_HOME") {
+    Ok(cargo_home) => PathBuf::from(cargo_home),
+    Err(_) => PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".cargo")
+  }
+}
+
 
 // Solution Architect
 #[derive(Debug)]
 pub struct AgentBackendDeveloper {
   attributes: BasicAgent,
-  bug_errors: Option<String>,
+  workspace: Workspace,
+  sandbox: Arc<dyn Sandbox>,
+  approver: Approver,
+  gates: Vec<QualityGate>,
+  vendor_dir: Option<PathBuf>,
+  storage: Option<StorageProfile>,
+  crates: Vec<AllowedCrate>,
+  module_plan: ModulePlan,
+  reviewed_code: Option<Vec<SourceFile>>,
+  has_written_tests: bool,
+  approved_tests: Option<String>,
+  bug_errors: BTreeMap<String, String>,
   bug_count: u8
 }
 
 impl AgentBackendDeveloper {
-  pub fn new() -> Self {
+  pub fn new(workspace: Workspace, sandbox: Arc<dyn Sandbox>, approver: Approver, gates: Vec<QualityGate>, vendor_dir: Option<PathBuf>, storage: Option<StorageProfile>) -> Self {
 
     // Define attributes
     let attributes: BasicAgent = BasicAgent {
-      objective: "Develops backend code for webserver and json database".to_string(),
       position: "Backend Developer".to_string(),
-      state: AgentState::Discovery,
-      memory: vec![]
+      state: AgentState::Discovery
     };
 
     // Return Self
     Self {
       attributes,
-      bug_errors: None,
+      workspace,
+      sandbox,
+      approver,
+      gates,
+      vendor_dir,
+      storage,
+      crates: vec![],
+      module_plan: ModulePlan::default(),
+      reviewed_code: None,
+      has_written_tests: false,
+      approved_tests: None,
+      bug_errors: BTreeMap::new(),
       bug_count: 0
     }
   }
 
+  // Keep errors by file for the next fix, failing the agent once the retry budget is spent
+  fn record_bugs(&mut self, errors: BTreeMap<String, String>) -> Result<(), Box<dyn std::error::Error + Send>> {
+    self.bug_count += 1;
+    self.bug_errors = errors;
+
+    // Exit if too many bug counts
+    if self.bug_count > 2 {
+      PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), "Exiting agent. Too many bugs found in code.");
+      return Err(Box::new(std::io::Error::other("Too many bugs found in backend code")));
+    }
+    Ok(())
+  }
+
   // AI Call: Write initial backend webserver code
   async fn call_initial_backend_code(&mut self, factsheet: &mut FactSheet) {
 
+    // Switch to the storage profile picked on the command line, else the one in the project scope
+    let storage: StorageProfile = self.storage
+      .or(factsheet.project_scope.and_then(|scope| scope.storage))
+      .unwrap_or_default();
+    self.storage = Some(storage);
+    save_storage_template(&self.workspace, storage);
+    self.call_write_migration(factsheet, storage).await;
+
     // Extract Code Template
-    let code_template_str: String = read_code_template_contents();
+    let code_template_str: String = read_code_template_contents(&self.workspace);
 
     // Concatenate instruction
     let mut msg_context: String = format!(
@@ -70,12 +174,22 @@ impl AgentBackendDeveloper {
     }
 
     // Adjust Instruction - Ignore creating external links
-    if !factsheet.project_scope.unwrap().is_user_login_and_logout {
+    let is_login_required: bool = factsheet.project_scope.unwrap().is_user_login_and_logout;
+    if !is_login_required {
       msg_context = format!("{} IMPORTANT IGNORE USER REGISTRATION AND LOGIN: Even though the CODE_TEMPLATE shows how to manage User credentials,
       you can REMOVE this functionality from your code and just use the basic CRUD operations as shown.", 
       msg_context);
     }
 
+    // Adjust Instruction - Secure user login with the auth module
+    if is_login_required {
+      msg_context = format!("{} IMPORTANT SECURE USER LOGIN: The auth module is already written, so never store or compare plain passwords as the CODE_TEMPLATE does.
+      Register hashes the password with hash_password before storing it. Login checks it with verify_password and returns {{\"token\": ...}} from AuthKeys::issue_token.
+      POST /logout revokes the token. Every route other than /register and /login is wrapped in require_auth, exactly as shown in AUTH_USAGE.
+      AUTH_USAGE: {}", 
+      msg_context, AUTH_USAGE);
+    }
+
     // Adjust Instruction - Ignore creating external links
     if !factsheet.project_scope.unwrap().is_crud_required {
       msg_context = format!("{} IMPORTANT IGNORE USER REGISTRATION AND LOGIN: Even though the CODE_TEMPLATE shows how to use CRUD,
@@ -83,6 +197,33 @@ impl AgentBackendDeveloper {
       msg_context);
     }
 
+    // Pick any extra crates before code is written against them
+    self.call_select_crates(factsheet).await;
+    msg_context = format!("{}{}{}", self.storage_context(), self.crates_context(), msg_context);
+
+    // Plan module layout, keeping to a single main.rs if the plan is unusable
+    self.call_plan_backend_modules(&msg_context).await;
+
+    // Login needs the fixed auth module in place of any the plan had, which also means writing separate files
+    if is_login_required {
+      self.module_plan = with_auth_module(self.module_plan.clone());
+      save_module_plan(&self.workspace, &self.module_plan);
+      save_backend_source(&self.workspace, &SourceFile { path: SourceFile::module_path(AUTH_MODULE_NAME), code: AUTH_MODULE_CODE.to_string() });
+    }
+    if !self.module_plan.modules.is_empty() {
+
+      // Write each module against the shared signatures, then main.rs
+      for module in self.module_plan.modules.clone() {
+        if is_login_required && module.name == AUTH_MODULE_NAME {
+          continue;
+        }
+        self.call_write_module(&msg_context, &SourceFile::module_path(&module.name), &module.purpose).await;
+      }
+      self.call_write_module(&msg_context, "src/main.rs", "declares the modules, registers routes and starts the webserver").await;
+      factsheet.backend_code = Some(combine_sources(&read_backend_sources(&self.workspace)));
+      return;
+    }
+
     // Retrieve AI Reponse
     let ai_response: String = ai_task_request(
       msg_context, 
@@ -91,70 +232,344 @@ impl AgentBackendDeveloper {
       print_backend_webserver_code).await;
     
     // Save code and update state
-    save_backend_code(&ai_response);
+    save_backend_code(&self.workspace, &ai_response);
+    record_step(&self.workspace, &self.attributes.position, get_function_string!(print_backend_webserver_code), &self.attributes.state);
     factsheet.backend_code = Some(ai_response);
   }
 
 
-  // AI Call: Write improved backend webserver code
-  async fn call_improved_backend_code(&mut self, factsheet: &mut FactSheet) {
+  // AI Call: Pick extra crates from the allowlist and pin them in Cargo.toml
+  async fn call_select_crates(&mut self, factsheet: &FactSheet) {
 
     // Structure message context
-    let msg_context: String = format!("CODE_TEMPLATE: {:?}, PROJECT_DESCRIPTION: {:?}. 
-      THIS FUNCTION ONLY OUTPUTS CODE. JUST OUTPUT THE CODE.", factsheet.backend_code, factsheet);
+    let msg_context: String = format!("PROJECT_DESCRIPTION: {}, ALLOWED_CRATES: {}",
+      factsheet.project_description, allowlist_description());
 
     // Retrieve AI Reponse
     let ai_response: String = ai_task_request(
       msg_context, 
       &self.attributes.position, 
-      get_function_string!(print_improved_webserver_code), 
-      print_improved_webserver_code).await;
+      get_function_string!(print_backend_crates), 
+      print_backend_crates).await;
+
+    // Keep to the allowlist, carrying on with the template's crates if the response is unusable
+    self.crates = match select_crates(&ai_response) {
+      Ok((crates, rejected)) => {
+        if !rejected.is_empty() {
+          let err_msg: String = format!("Ignoring crates not on the allowlist: {}", rejected.join(", "));
+          PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
+        }
+        crates
+      },
+      Err(e) => {
+        let err_msg: String = format!("Unable to use crate selection ({}), using the template's crates only", e);
+        PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
+        vec![]
+      }
+    };
 
-    // Update and continue
-    save_backend_code(&ai_response);
-    factsheet.backend_code = Some(ai_response);
+    // Storage crates, and auth crates when users log in, are always needed, whatever was picked
+    let mut required_crates: Vec<AllowedCrate> = self.storage.unwrap_or_default().crates();
+    if factsheet.project_scope.is_some_and(|scope| scope.is_user_login_and_logout) {
+      required_crates.extend(auth_crates());
+    }
+    for allowed in required_crates {
+      if !self.crates.contains(&allowed) {
+        self.crates.push(allowed);
+      }
+    }
+
+    // Save pinned crates, resolving them from the vendored registry if there is one
+    save_backend_dependencies(&self.workspace, &self.crates);
+    if let Some(vendor_dir) = &self.vendor_dir {
+      save_vendor_config(&self.workspace, vendor_dir);
+    }
+    record_step(&self.workspace, &self.attributes.position, get_function_string!(print_backend_crates), &self.attributes.state);
   }
 
 
-  // AI Call: Fix bugs in code
-  async fn call_fix_code_bugs(&mut self, factsheet: &mut FactSheet) {
+  // AI Call: Write a migration adding the storage the project needs to the template's starting migrations
+  async fn call_write_migration(&self, factsheet: &FactSheet, storage: StorageProfile) {
 
     // Structure message context
-    let msg_context: String = format!("BROKEN_CODE: {:?}, ERROR_BUGS: {:?}. 
-      THIS FUNCTION ONLY OUTPUTS CODE. JUST OUTPUT THE CODE.", factsheet.backend_code, self.bug_errors);
+    let msg_context: String = format!("STORAGE: {}, MIGRATIONS:\n{}\nPROJECT_DESCRIPTION: {}",
+      storage.description(), read_migrations(&self.workspace), factsheet.project_description);
 
     // Retrieve AI Reponse
     let ai_response: String = ai_task_request(
       msg_context, 
       &self.attributes.position, 
-      get_function_string!(print_fixed_code), 
-      print_fixed_code).await;
+      get_function_string!(print_storage_migration), 
+      print_storage_migration).await;
+
+    // Only keep migrations the server can apply, as a bad one stops it starting
+    match storage.validate_migration(&ai_response) {
+      Ok(()) => {
+        let file_name: String = format!("0002_project.{}", storage.migration_extension());
+        save_migration(&self.workspace, &file_name, &ai_response);
+        record_step(&self.workspace, &self.attributes.position, get_function_string!(print_storage_migration), &self.attributes.state);
+      },
+      Err(e) => {
+        let err_msg: String = format!("Unable to use migration ({}), keeping the template's migrations only", e);
+        PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
+      }
+    }
+  }
 
-    // Update and continue
-    save_backend_code(&ai_response);
-    factsheet.backend_code = Some(ai_response);
+
+  // Storage profile and its migrations to share with prompts, once picked
+  fn storage_context(&self) -> String {
+    match self.storage {
+      Some(storage) => format!("STORAGE: {}\nMIGRATIONS:\n{}\n", storage.description(), read_migrations(&self.workspace)),
+      None => String::new()
+    }
   }
 
 
-  // AI Call: Extract REST API Endpoints
-  async fn call_extract_rest_api_endpoints(&self) -> String {
+  // Extra crates to share with prompts, if any were picked
+  fn crates_context(&self) -> String {
+    match self.crates.is_empty() {
+      true => String::new(),
+      false => format!("EXTRA_CRATES: {}\n", self.crates.iter().map(|allowed| allowed.name).collect::<Vec<&str>>().join(", "))
+    }
+  }
+
+
+  // AI Call: Plan which modules the backend is split into
+  async fn call_plan_backend_modules(&mut self, msg_context: &str) {
+
+    // Retrieve AI Reponse
+    let ai_response: String = ai_task_request(
+      msg_context.to_string(), 
+      &self.attributes.position, 
+      get_function_string!(print_backend_module_plan), 
+      print_backend_module_plan).await;
+
+    // Check plan can be written out
+    let plan_res: Result<ModulePlan, String> = serde_json::from_str::<ModulePlan>(&ai_response)
+      .map_err(|e| e.to_string())
+      .and_then(|module_plan| module_plan.validate().map(|_| module_plan));
+    self.module_plan = match plan_res {
+      Ok(module_plan) => module_plan,
+      Err(e) => {
+        let err_msg: String = format!("Unable to use module plan ({}), writing a single main.rs", e);
+        PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
+        ModulePlan::default()
+      }
+    };
+    save_module_plan(&self.workspace, &self.module_plan);
+    record_step(&self.workspace, &self.attributes.position, get_function_string!(print_backend_module_plan), &self.attributes.state);
+  }
+
 
-    // Get latest backend code from file (so can run separately when running cargo test)
-    let path: String = format!("{}/src/main.rs", BACKEND_CODE_DIR);
-    let backend_code: String = fs::read_to_string(path).expect("Something went wrong reading the file");
+  // AI Call: Write one file of the backend from the module plan
+  async fn call_write_module(&self, msg_context: &str, path: &str, purpose: &str) {
 
     // Structure message context
-    let msg_context: String = format!("CODE_INPUT: {:?}", backend_code);
+    let module_context: String = format!("MODULE: {} ({}), MODULE_PLAN:\n{}\n{}",
+      path, purpose, self.module_plan.signatures(), msg_context);
+
+    // Retrieve AI Reponse
+    let ai_response: String = ai_task_request(
+      module_context, 
+      &self.attributes.position, 
+      get_function_string!(print_backend_module_code), 
+      print_backend_module_code).await;
+
+    // Save code
+    self.save_source(SourceFile { path: path.to_string(), code: ai_response });
+    record_step(&self.workspace, &self.attributes.position, get_function_string!(print_backend_module_code), &self.attributes.state);
+  }
+
+
+  // Save a source file, making sure main.rs declares every planned module
+  fn save_source(&self, mut source: SourceFile) {
+    if source.path == "src/main.rs" {
+      for module in &self.module_plan.modules {
+        source.code = declare_module(&source.code, &module.name, false);
+      }
+    }
+    save_backend_source(&self.workspace, &source);
+  }
+
+
+  // Module plan to share with prompts about a single file, if the backend has more than one
+  fn module_plan_context(&self) -> String {
+    match self.module_plan.modules.is_empty() {
+      true => String::new(),
+      false => format!("MODULE_PLAN:\n{}\n", self.module_plan.signatures())
+    }
+  }
+
+
+  // AI Call: Ask for edit blocks to a file, returning the patched code if they apply cleanly
+  async fn call_code_edits(&self, code: &str, msg_context: String, ai_func_name: &str, ai_function: for<'a> fn(&'a str) -> &'static str) -> Result<String, PatchError> {
 
     // Retrieve AI Reponse
     let ai_response: String = ai_task_request(
       msg_context, 
       &self.attributes.position, 
-      get_function_string!(print_rest_api_endpoints), 
-      print_rest_api_endpoints).await;
+      ai_func_name, 
+      ai_function).await;
+
+    // Apply edits, leaving the caller to decide what to do if they do not fit
+    parse_edit_blocks(&ai_response).and_then(|blocks| apply_edit_blocks(code, &blocks))
+  }
+
+
+  // AI Call: Write improved backend webserver code, one file at a time
+  async fn call_improved_backend_code(&mut self, factsheet: &mut FactSheet) {
+    for source in read_backend_sources(&self.workspace) {
 
-    // Return response
-    return ai_response;
+      // The fixed auth module is kept as written
+      if source.code == AUTH_MODULE_CODE {
+        continue;
+      }
+
+      // Try targeted edits first, leaving files which need nothing as they are
+      let edit_context: String = format!("PROJECT_DESCRIPTION: {:?}, {}{}{}FILE: {}, CODE_INPUT:\n{}",
+        factsheet.project_description, self.storage_context(), self.crates_context(), self.module_plan_context(), source.path, source.code);
+      let ai_func_name: &str = get_function_string!(print_improved_webserver_code_edits);
+      match self.call_code_edits(&source.code, edit_context, ai_func_name, print_improved_webserver_code_edits).await {
+        Ok(patched_code) => {
+          self.save_source(SourceFile { code: patched_code, ..source });
+          record_step(&self.workspace, &self.attributes.position, ai_func_name, &self.attributes.state);
+          continue;
+        },
+        Err(PatchError::NoEdits) => continue,
+        Err(e) => {
+          let err_msg: String = format!("Unable to apply edits to {} ({}), rewriting whole file...", source.path, e);
+          PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
+        }
+      }
+
+      // Structure message context
+      let msg_context: String = format!("CODE_TEMPLATE: {:?}, {}{}{}PROJECT_DESCRIPTION: {:?}. 
+        THIS FUNCTION ONLY OUTPUTS CODE. JUST OUTPUT THE CODE.", source.code, self.storage_context(), self.crates_context(), self.module_plan_context(), factsheet);
+
+      // Retrieve AI Reponse
+      let ai_response: String = ai_task_request(
+        msg_context, 
+        &self.attributes.position, 
+        get_function_string!(print_improved_webserver_code), 
+        print_improved_webserver_code).await;
+
+      // Update and continue
+      self.save_source(SourceFile { code: ai_response, ..source });
+      record_step(&self.workspace, &self.attributes.position, get_function_string!(print_improved_webserver_code), &self.attributes.state);
+    }
+    factsheet.backend_code = Some(combine_sources(&read_backend_sources(&self.workspace)));
+  }
+
+
+  // Server files, plus the integration tests when failures point at them, as a wrong test can only be fixed in the tests
+  fn sources_to_fix(&self) -> Vec<SourceFile> {
+    let mut sources: Vec<SourceFile> = read_backend_sources(&self.workspace);
+    if self.bug_errors.contains_key(TESTS_SOURCE_PATH) {
+      sources.push(SourceFile { path: TESTS_SOURCE_PATH.to_string(), code: read_backend_tests(&self.workspace) });
+    }
+    sources
+  }
+
+
+  // AI Call: Fix bugs in code
+  // Each file only gets its own errors, and server files also get any which could not be pinned on a server file
+  async fn call_fix_code_bugs(&mut self, factsheet: &mut FactSheet) {
+    let sources: Vec<SourceFile> = self.sources_to_fix();
+    let is_server_file = |path: &str| -> bool { path != TESTS_SOURCE_PATH && sources.iter().any(|source| source.path == path) };
+    for source in sources.clone() {
+      let errors: Vec<&String> = self.bug_errors.iter()
+        .filter(|(file, _)| **file == source.path || (is_server_file(&source.path) && !is_server_file(file.as_str())))
+        .map(|(_, errors)| errors)
+        .collect();
+      if errors.is_empty() {
+        continue;
+      }
+      let is_failing_file: bool = self.bug_errors.contains_key(&source.path);
+      let error_bugs: String = errors.into_iter().cloned().collect::<Vec<String>>().join("\n");
+
+      // Try targeted edits first
+      let edit_context: String = format!("ERROR_BUGS: {:?}, {}FILE: {}, CODE_INPUT:\n{}",
+        error_bugs, self.module_plan_context(), source.path, source.code);
+      let ai_func_name: &str = get_function_string!(print_fixed_code_edits);
+      match self.call_code_edits(&source.code, edit_context, ai_func_name, print_fixed_code_edits).await {
+        Ok(patched_code) => {
+          self.save_source(SourceFile { code: patched_code, ..source });
+          record_step(&self.workspace, &self.attributes.position, ai_func_name, &self.attributes.state);
+          continue;
+        },
+        Err(PatchError::NoEdits) if !is_failing_file => continue,
+        Err(e) => {
+          let err_msg: String = format!("Unable to apply edits to {} ({}), rewriting whole file...", source.path, e);
+          PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
+        }
+      }
+
+      // Structure message context
+      let msg_context: String = format!("BROKEN_CODE: {:?}, {}ERROR_BUGS: {:?}. 
+        THIS FUNCTION ONLY OUTPUTS CODE. JUST OUTPUT THE CODE.", source.code, self.module_plan_context(), error_bugs);
+
+      // Retrieve AI Reponse
+      let ai_response: String = ai_task_request(
+        msg_context, 
+        &self.attributes.position, 
+        get_function_string!(print_fixed_code), 
+        print_fixed_code).await;
+
+      // Update and continue
+      self.save_source(SourceFile { code: ai_response, ..source });
+      record_step(&self.workspace, &self.attributes.position, get_function_string!(print_fixed_code), &self.attributes.state);
+    }
+    factsheet.backend_code = Some(combine_sources(&read_backend_sources(&self.workspace)));
+  }
+
+
+  // AI Call: Write integration tests for every saved API endpoint
+  async fn call_write_integration_tests(&mut self, factsheet: &FactSheet) {
+
+    // Structure message context
+    let msg_context: String = format!("API_ENDPOINTS_JSON_SCHEMA: {}, CODE_INPUT: {:?}",
+      read_api_endpoints(&self.workspace), factsheet.backend_code);
+    let msg_context: String = format!("{}{}", self.module_plan_context(), msg_context);
+
+    // Retrieve AI Reponse
+    let ai_response: String = ai_task_request(
+      msg_context, 
+      &self.attributes.position, 
+      get_function_string!(print_backend_integration_tests), 
+      print_backend_integration_tests).await;
+
+    // Save tests alongside the webserver code
+    save_backend_tests(&self.workspace, &ai_response);
+    declare_backend_tests(&self.workspace);
+    record_step(&self.workspace, &self.attributes.position, get_function_string!(print_backend_integration_tests), &self.attributes.state);
+  }
+
+
+  // AI Call: Describe REST API Endpoints extracted from the code
+  async fn call_describe_rest_api_endpoints(&self, backend_code: &str, api_endpoints: &mut [RouteObject]) {
+
+    // Structure message context
+    let routes: Vec<String> = api_endpoints.iter()
+      .map(|endpoint| format!("{} {}", endpoint.method, endpoint.route)).collect();
+    let msg_context: String = format!("ROUTES: {:?}, CODE_INPUT: {:?}", routes, backend_code);
+
+    // Retrieve AI Reponse
+    let ai_response: String = ai_task_request(
+      msg_context, 
+      &self.attributes.position, 
+      get_function_string!(print_rest_api_endpoint_descriptions), 
+      print_rest_api_endpoint_descriptions).await;
+
+    // Descriptions are optional, so carry on without them if they do not line up
+    match serde_json::from_str::<Vec<String>>(&ai_response) {
+      Ok(descriptions) if descriptions.len() == api_endpoints.len() => {
+        for (endpoint, description) in api_endpoints.iter_mut().zip(descriptions) {
+          endpoint.description = Some(description);
+        }
+      },
+      _ => PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), "Unable to use endpoint descriptions, continuing without them")
+    }
   }
 }
 
@@ -166,12 +581,22 @@ impl SpecialFunctions for AgentBackendDeveloper {
     &self.attributes
   }
 
-  async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error>> {
+  // Needs the project scope to know what to build
+  fn reads(&self) -> Vec<FactSheetField> {
+    vec![FactSheetField::ProjectDescription, FactSheetField::ProjectScope]
+  }
+
+  // Writes the webserver and works out its endpoints
+  fn produces(&self) -> Vec<FactSheetField> {
+    vec![FactSheetField::BackendCode, FactSheetField::ApiEndpointSchema]
+  }
+
+  async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error + Send>> {
 
     // Get project scope items
     let (is_crud_required, is_user_login_and_logout): (bool, bool) = match &factsheet.project_scope {
       Some(scope) => (scope.is_crud_required, scope.is_user_login_and_logout),
-      None => return Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, "Must contain project scope before starting on Backend work"))),
+      None => return Err(Box::new(std::io::Error::other("Must contain project scope before starting on Backend work"))),
     };
 
     // Continue until finished
@@ -184,8 +609,10 @@ impl SpecialFunctions for AgentBackendDeveloper {
         // Write initial backend code
         AgentState::Discovery => {
 
-          // Guard: Ensure backend is required
+          // Guard: Ensure backend is required, leaving static sites an empty API for the frontend
           if !is_crud_required && !is_user_login_and_logout {
+            factsheet.api_endpoint_schema = Some(vec![]);
+            save_openapi_spec(&self.workspace, &openapi_document(&factsheet.project_description, &[]));
             self.attributes.state = AgentState::Finished;
             continue;
           }
@@ -222,74 +649,120 @@ impl SpecialFunctions for AgentBackendDeveloper {
 
           // Guard: Ensure safe code
           PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Backend Unit Testing: ensure safe code...");
-          let is_safe_code: bool = confirm_safe_code();
-          if !is_safe_code {
-            panic!("Better go work on some AI alignment instead...")
+          let backend_sources: Vec<SourceFile> = read_backend_sources(&self.workspace);
+          let previous_sources: Vec<SourceFile> = self.reviewed_code.clone().unwrap_or_else(|| vec![
+            SourceFile { path: "src/main.rs".to_string(), code: read_code_template_contents(&self.workspace) }
+          ]);
+          let allowed_hosts: Vec<String> = hosts_from_urls(factsheet.external_urls.as_deref().unwrap_or_default());
+          let safety_report: SafetyReport = SafetyReport::new(&previous_sources, &backend_sources, &allowed_hosts);
+          if !self.approver.approve(&self.attributes.position, &safety_report).await {
+            return Err(Box::new(std::io::Error::other("Generated backend code was not approved to run")));
+          }
+          self.reviewed_code = Some(backend_sources);
+
+          // Fetch dependencies on the host so the sandboxed build can run offline
+          PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Backend Unit Testing: fetching dependencies...");
+          let fetch_status: std::process::ExitStatus = Command::new("cargo")
+            .arg("fetch")
+            .current_dir(&self.workspace.backend_dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .expect("Failed to fetch backend dependencies");
+          if !fetch_status.success() {
+            PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), "Unable to fetch all dependencies, build may fail offline");
           }
 
-          // Build backend application
+          // Build backend application inside the sandbox
+          // Cargo home stays writable for its package cache lock
           PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Backend Unit Testing: building...");
-          let build_backend_server: std::process::Output = Command::new("cargo")
-            .arg("build")
-            .current_dir(BACKEND_CODE_DIR)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+          let mut build_command: SandboxCommand = SandboxCommand::new("cargo", &["build", "--offline", "--message-format=json"], &self.workspace.backend_dir);
+          build_command.writable.push(cargo_home());
+          let build_backend_server: std::process::Output = run_sandboxed(self.sandbox.as_ref(), &build_command)
             .expect("Failed to run the backend application");
 
           // Determine if build errors
           if build_backend_server.status.success() {
-            self.bug_count = 0;
             PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Test server build successful...");
           } else {
-            let error_arr: Vec<u8> = build_backend_server.stderr;
-            let error_str: String = String::from_utf8(error_arr).unwrap();
-
-            // Update error stats
-            self.bug_count += 1;
-            self.bug_errors = Some(error_str);
-
-            // Exit if too many bug counts
-            if self.bug_count > 2 {
-              PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), "Exiting agent. Too many bugs found in code.");
-              panic!("Error: Too many bugs")
+            // Group compiler errors by file, falling back to raw output if there are none
+            let build_stdout: String = String::from_utf8_lossy(&build_backend_server.stdout).to_string();
+            let mut build_errors: BTreeMap<String, String> = errors_by_file(&parse_compiler_messages(&build_stdout));
+            if build_errors.is_empty() {
+              build_errors.insert(ANY_FILE.to_string(), String::from_utf8_lossy(&build_backend_server.stderr).to_string());
             }
+            self.record_bugs(labelled("BUILD_ERRORS", build_errors))?;
 
             // Pass back for rework
             self.attributes.state = AgentState::Working;
             continue;
           }
 
-          // Extract API Endpoints
-          let api_endpoints_str: String = self.call_extract_rest_api_endpoints().await;
-
-          // Convert API Endpoints into Values
-          let api_endpoints: Vec<RouteObject> = serde_json::from_str(api_endpoints_str.as_str())
-            .expect("Failed to decode API Endpoints");
+          // Run optional quality gates, passing their diagnostics back as structured errors
+          let mut diagnostics: Vec<Diagnostic> = vec![];
+          for gate in &self.gates {
+            let gate_msg: String = format!("Backend Unit Testing: running {:?} gate...", gate);
+            PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), gate_msg.as_str());
+            let mut gate_command: SandboxCommand = gate.command(&self.workspace.backend_dir);
+            gate_command.writable.push(cargo_home());
+            let gate_output: std::process::Output = run_sandboxed(self.sandbox.as_ref(), &gate_command)
+              .expect("Failed to run quality gate");
+            diagnostics.extend(gate.diagnostics(&gate_output, &self.workspace.backend_dir));
+          }
+          if !diagnostics.is_empty() {
+            for diagnostic in &diagnostics {
+              PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), diagnostic.to_string().as_str());
+            }
+            self.record_bugs(labelled("QUALITY_GATE_ERRORS", errors_by_file(&diagnostics)))?;
 
-          // Extract API Endpoints
-          let check_endpoints: Vec<RouteObject> = api_endpoints.iter()
-            .filter(|&route_object| route_object.method == "get" && route_object.is_route_dynamic == "false")
-            .cloned()
-            .collect();
+            // Pass back for rework
+            self.attributes.state = AgentState::Working;
+            continue;
+          }
 
-          // Store API Endpoints
-          factsheet.api_endpoint_schema = Some(check_endpoints.clone());
+          // Extract API Endpoints from the code, then describe them
+          let backend_code: String = combine_sources(&read_backend_sources(&self.workspace));
+          let mut api_endpoints: Vec<RouteObject> = match extract_routes(&backend_code) {
+            Ok(routes) => routes,
+            Err(e) => return Err(Box::new(std::io::Error::other(format!("Unable to extract routes: {}", e)))),
+          };
+          self.call_describe_rest_api_endpoints(&backend_code, &mut api_endpoints).await;
+          let api_endpoints_str: String = serde_json::to_string_pretty(&api_endpoints)
+            .expect("Failed to encode API Endpoints");
+
+          // Describe the API as an OpenAPI document, which the endpoints are then tested against
+          let openapi_spec: Value = openapi_document(&factsheet.project_description, &api_endpoints);
+          let spec_endpoints: Vec<RouteObject> = match routes_from_openapi(&openapi_spec) {
+            Ok(routes) => routes,
+            Err(e) => return Err(Box::new(std::io::Error::other(format!("Unable to read OpenAPI document: {}", e)))),
+          };
+
+          // Store every API Endpoint for the frontend, not just the ones which can be probed
+          factsheet.api_endpoint_schema = Some(api_endpoints.clone());
 
           // Build backend application
           PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Backend Unit Testing: Starting server...");
-          let mut run_backend_server: std::process::Child = Command::new("cargo")
-            .arg("run")
-            .current_dir(BACKEND_CODE_DIR)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .expect("Failed to run the backend application");
+          // Server runs on a free port and shares the host network so endpoints can be called from here
+          let binary_path: String = self.workspace.backend_binary_path().to_string_lossy().to_string();
+          let run_command: SandboxCommand = SandboxCommand::new(&binary_path, &[], &self.workspace.backend_dir);
+          let mut backend_server: ServerSupervisor = match ServerSupervisor::start(self.sandbox.as_ref(), run_command, "Backend Server") {
+            Ok(backend_server) => backend_server,
+            Err(e) => return Err(Box::new(std::io::Error::other(format!("Failed to run the backend application: {}", e)))),
+          };
+
+          // Wait for server to start listening
+          let waiting_msg: String = format!("Waiting for server on port {}...", backend_server.port);
+          PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), waiting_msg.as_str());
+          if let Err(e) = backend_server.wait_until_ready(SERVER_READY_TIMEOUT).await {
+            let server_stderr: String = backend_server.stop();
+            PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), e.to_string().as_str());
+            let server_errors: String = format!("SERVER_START_FAILURE: {}\nSERVER_STDERR:\n{}", e, log_tail(&server_stderr));
+            self.record_bugs(BTreeMap::from([(ANY_FILE.to_string(), server_errors)]))?;
 
-          // Sleep for 5 seconds
-          PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Launching tests on server in 5 seconds...");
-          let seconds_sleep: Duration = Duration::from_secs(5);
-          time::sleep(seconds_sleep).await;
+            // Pass back for rework
+            self.attributes.state = AgentState::Working;
+            continue;
+          }
 
           // Create client with timeout
           let client: Client = Client::builder()
@@ -297,37 +770,75 @@ impl SpecialFunctions for AgentBackendDeveloper {
             .build()
             .unwrap();
 
-          // Check status code
-          for endpoint in check_endpoints {
-
-            // Confirm url testing
-            let testing_msg: String = format!("Testing endpoint '{}'...", endpoint.route);
-            PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), testing_msg.as_str());
-
-            // Test url
-            let url: String = format!("http://localhost:8080{}", endpoint.route);
-            match check_status_code(&client, &url).await {
-              Ok(status_code) => {
-                if status_code != 200 {
-                  let err_msg: String = format!("WARNING: Failed to call backend url endpoint {}", endpoint.route);
-                  PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
-                }
-              }
-              Err(e) => {
-                // kill $(lsof -t -i:8080)
-                run_backend_server.kill().expect("Failed to kill the backend web server");
-                let err_msg: String = format!("Error checking backend: {}", e);
-                PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
-              },
-            }
+          // Exercise every route, creating resources before reading, updating and deleting them
+          let endpoint_report: EndpointReport = run_endpoint_tests(&client, &backend_server.url(), &spec_endpoints).await;
+          PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), format!("Endpoint results:\n{}", endpoint_report.matrix()).as_str());
+          if endpoint_report.is_passing() {
+            PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "All endpoints passed...");
+          }
+          for failure in endpoint_report.failures() {
+            let err_msg: String = format!("WARNING: Endpoint {} {} failed", failure.method.to_uppercase(), failure.route);
+            PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), err_msg.as_str());
+          }
+
+          // Runtime failures count as bugs, along with what the server logged
+          if !endpoint_report.is_passing() {
+            let server_stderr: String = backend_server.stop();
+            let endpoint_errors: String = format!("ENDPOINT_FAILURES:\n{}SERVER_STDERR:\n{}", endpoint_report.failure_summary(), log_tail(&server_stderr));
+            self.record_bugs(BTreeMap::from([(ANY_FILE.to_string(), endpoint_errors)]))?;
+
+            // Pass back for rework
+            self.attributes.state = AgentState::Working;
+            continue;
           }
 
           // Save API Endpoints
-          save_api_endpoints(&api_endpoints_str);
+          save_api_endpoints(&self.workspace, &api_endpoints_str);
+          save_openapi_spec(&self.workspace, &openapi_spec);
+          record_step(&self.workspace, &self.attributes.position, get_function_string!(print_rest_api_endpoint_descriptions), &self.attributes.state);
 
           // Kill backend server
+          PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Endpoint testing complete...");
+          backend_server.stop();
+
+          // Write integration tests once, checking them like any other AI written code whenever a fix changes them
+          if !self.has_written_tests {
+            self.call_write_integration_tests(factsheet).await;
+            self.has_written_tests = true;
+          }
+          let tests_code: String = read_backend_tests(&self.workspace);
+          if self.approved_tests.as_ref() != Some(&tests_code) {
+            let tests_source: SourceFile = SourceFile { path: TESTS_SOURCE_PATH.to_string(), code: tests_code.clone() };
+            let tests_report: SafetyReport = SafetyReport::new(&[], &[tests_source], &allowed_hosts);
+            if !self.approver.approve(&self.attributes.position, &tests_report).await {
+              return Err(Box::new(std::io::Error::other("Generated backend tests were not approved to run")));
+            }
+            self.approved_tests = Some(tests_code);
+          }
+
+          // Run integration tests, passing failures back as structured errors
+          PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Backend Unit Testing: running integration tests...");
+          declare_backend_tests(&self.workspace);
+          let mut test_command: SandboxCommand = QualityGate::Test.command(&self.workspace.backend_dir);
+          test_command.writable.push(cargo_home());
+          let test_output: std::process::Output = run_sandboxed(self.sandbox.as_ref(), &test_command)
+            .expect("Failed to run integration tests");
+          let test_diagnostics: Vec<Diagnostic> = QualityGate::Test.diagnostics(&test_output, &self.workspace.backend_dir);
+          if !test_diagnostics.is_empty() {
+            for diagnostic in &test_diagnostics {
+              PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), diagnostic.to_string().as_str());
+            }
+            let tests_code: String = read_backend_tests(&self.workspace);
+            let test_errors: BTreeMap<String, String> = labelled("INTEGRATION_TEST_FAILURES", errors_by_file(&test_diagnostics)).into_iter()
+              .map(|(file, errors)| (file, format!("{}\nTEST_CODE: {}", errors, tests_code)))
+              .collect();
+            self.record_bugs(test_errors)?;
+
+            // Pass back for rework
+            self.attributes.state = AgentState::Working;
+            continue;
+          }
           PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Backend testing complete...");
-          run_backend_server.kill().expect("Failed to kill the backend web server");
 
           // Update agent state to finished
           self.attributes.state = AgentState::Finished;
@@ -346,21 +857,69 @@ impl SpecialFunctions for AgentBackendDeveloper {
 #[cfg(test)]
 pub mod tests {
   use super::*;
+  use crate::helpers::sandbox::detect_sandbox;
+  use crate::helpers::approval::ApprovalPolicy;
+  use crate::helpers::general::{read_backend_code, read_openapi_spec, save_backend_tests};
+  use crate::helpers::scaffold::scaffold_project;
+
+  // Scaffolded project in the temp folder for the agent to write into
+  fn backend_workspace(label: &str) -> Workspace {
+    let workspace: Workspace = Workspace::temporary(label);
+    scaffold_project(&workspace).expect("Failed to scaffold project");
+    workspace
+  }
+
+
+  #[tokio::test]
+  async fn publishes_empty_api_for_static_site() {
+    let workspace: Workspace = backend_workspace("static-site");
+    let mut agent: AgentBackendDeveloper = AgentBackendDeveloper::new(workspace.clone(), detect_sandbox(true).unwrap(), Approver::new(ApprovalPolicy::Deny, &std::env::temp_dir()), vec![], None, None);
+    let mut factsheet: FactSheet = serde_json::from_str("{\"project_description\":\"Build a portfolio site\",\"project_scope\":{\"is_crud_required\":false,\"is_user_login_and_logout\":false,\"is_external_urls_required\":false},\"external_urls\":null,\"backend_code\":null,\"api_endpoint_schema\":null}").unwrap();
+    agent.execute(&mut factsheet).await.expect("Unable to execute running agent");
+    let openapi_spec: Value = serde_json::from_str(&read_openapi_spec(&workspace)).unwrap();
+    std::fs::remove_dir_all(&workspace.root).unwrap();
+
+    assert_eq!(factsheet.api_endpoint_schema, Some(vec![]));
+    assert_eq!(openapi_spec["paths"], serde_json::json!({}));
+  }
+
+  #[test]
+  fn fixes_failing_integration_tests() {
+    let workspace: Workspace = backend_workspace("failing-tests");
+    save_backend_tests(&workspace, &"#[test]\nfn lists_tasks() { assert_eq!(1, 2); }\n".to_string());
+    let mut agent: AgentBackendDeveloper = AgentBackendDeveloper::new(workspace.clone(), detect_sandbox(true).unwrap(), Approver::new(ApprovalPolicy::Deny, &std::env::temp_dir()), vec![], None, None);
+    let server_paths: Vec<String> = agent.sources_to_fix().into_iter().map(|source| source.path).collect();
+    agent.bug_errors = BTreeMap::from([(TESTS_SOURCE_PATH.to_string(), "INTEGRATION_TEST_FAILURES: assertion failed".to_string())]);
+    let fix_paths: Vec<String> = agent.sources_to_fix().into_iter().map(|source| source.path).collect();
+    std::fs::remove_dir_all(&workspace.root).unwrap();
+
+    assert!(!server_paths.contains(&TESTS_SOURCE_PATH.to_string()));
+    assert_eq!(fix_paths.last(), Some(&TESTS_SOURCE_PATH.to_string()));
+  }
+
+  #[test]
+  fn fails_once_bug_budget_is_spent() {
+    let mut agent: AgentBackendDeveloper = AgentBackendDeveloper::new(Workspace::temporary("bug-budget"), detect_sandbox(true).unwrap(), Approver::new(ApprovalPolicy::Deny, &std::env::temp_dir()), vec![], None, None);
+    let errors: BTreeMap<String, String> = BTreeMap::from([(ANY_FILE.to_string(), "BUILD_ERRORS: mismatched types".to_string())]);
+    assert!(agent.record_bugs(errors.clone()).is_ok());
+    assert!(agent.record_bugs(errors.clone()).is_ok());
+    assert!(agent.record_bugs(errors).is_err());
+  }
 
 
   #[tokio::test]
   async fn develops_and_saves_website_backend() {
 
     // Create agent instance and site purpose
-    let mut agent: AgentBackendDeveloper = AgentBackendDeveloper::new();
+    let workspace: Workspace = backend_workspace("develops-backend");
+    let mut agent: AgentBackendDeveloper = AgentBackendDeveloper::new(workspace.clone(), detect_sandbox(true).unwrap(), Approver::new(ApprovalPolicy::Interactive, &std::env::temp_dir()), vec![], None, None);
 
     // Initialze Factsheet
     let mut factsheet: FactSheet = serde_json::from_str("{\"project_description\":\"Build a full stack website with user login and logout that shows latest Forex prices\",\"project_scope\":{\"is_crud_required\":true,\"is_user_login_and_logout\":true,\"is_external_urls_required\":true},\"external_urls\":[\"https://api.exchangeratesapi.io/latest\"],\"backend_code\":null,\"frontend_code\":null,\"json_db_schema\":null}").unwrap();
 
     // Execute running agent
     agent.execute(&mut factsheet).await.expect("Unable to execute running agent");
-    let contents: String = fs::read_to_string("/Users/shaun/Code/DEVELOPMENT/autogippity/website/backend/src/main.rs")
-      .expect("Failed to read code");
+    let contents: String = read_backend_code(&workspace);
     assert!(contents.len() > 100);
   }
 
@@ -368,7 +927,7 @@ pub mod tests {
   async fn tests_written_code() {
 
     // Create agent instance and site purpose
-    let mut agent: AgentBackendDeveloper = AgentBackendDeveloper::new();
+    let mut agent: AgentBackendDeveloper = AgentBackendDeveloper::new(backend_workspace("tests-written-code"), detect_sandbox(true).unwrap(), Approver::new(ApprovalPolicy::Interactive, &std::env::temp_dir()), vec![], None, None);
     agent.attributes.state = AgentState::UnitTesting;
 
     // Initialze Factsheet
@@ -0,0 +1,67 @@
+use crate::models::agent_basic::basic_agent::{BasicAgent, AgentState};
+use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet, FactSheetField};
+use crate::ai_functions::aifunc_frontend::print_recommended_site_main_colours;
+use crate::helpers::general::ai_task_request_decoded;
+use async_trait::async_trait;
+
+// Brand Designer
+// Only needs the project description, so can run alongside backend development
+#[derive(Debug)]
+pub struct AgentBrandDesigner {
+  attributes: BasicAgent
+}
+
+impl AgentBrandDesigner {
+  pub fn new() -> Self {
+
+    // Define attributes
+    let attributes: BasicAgent = BasicAgent {
+      position: "Brand Designer".to_string(),
+      state: AgentState::Discovery
+    };
+
+    // Return Self
+    Self {
+      attributes
+    }
+  }
+
+  // AI Call: Retrieve brand colours
+  async fn call_brand_colours(&mut self, factsheet: &mut FactSheet) {
+    let msg_context: String = format!("PROJECT_DESCRIPTION: {}", factsheet.project_description);
+
+    let ai_response: Vec<String> = ai_task_request_decoded::<Vec<String>>(
+      msg_context,
+      &self.attributes.position,
+      get_function_string!(print_recommended_site_main_colours),
+      print_recommended_site_main_colours).await;
+
+    // Update factsheet
+    factsheet.brand_colours = Some(ai_response);
+  }
+}
+
+
+#[async_trait]
+impl SpecialFunctions for AgentBrandDesigner {
+
+  fn get_attributes_from_agent(&self) -> &BasicAgent {
+    &self.attributes
+  }
+
+  // Only needs the project description
+  fn reads(&self) -> Vec<FactSheetField> {
+    vec![FactSheetField::ProjectDescription]
+  }
+
+  // Hands brand colours to the Frontend Developer
+  fn produces(&self) -> Vec<FactSheetField> {
+    vec![FactSheetField::BrandColours]
+  }
+
+  async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error + Send>> {
+    self.call_brand_colours(factsheet).await;
+    self.attributes.state = AgentState::Finished;
+    Ok(())
+  }
+}
@@ -9,19 +9,27 @@ use crate::helpers::general::{
   ai_task_request_decoded,
   ai_task_request,
   read_frontend_code_contents,
-  BACKEND_CODE_DIR,
-  FRONTEND_CODE_DIR
+  read_backend_sources,
+  read_openapi_spec
 };
-use crate::models::agents::agent_frontend_comp::BuildComponent;
-use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet};
+use crate::helpers::workspace::Workspace;
+use crate::helpers::modules::combine_sources;
+use crate::helpers::git::record_step;
+use crate::models::agents::agent_frontend_comp::{BuildComponent, ComponentSelection};
+use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet, FactSheetField};
 use crate::models::agent_basic::basic_agent::{BasicAgent, AgentState};
 use crate::helpers::command_line::PrintCommand;
+use crate::helpers::sandbox::{run_sandboxed, Sandbox, SandboxCommand};
+use crate::helpers::frontend_gates::FrontendGate;
+use crate::helpers::quality_gates::Diagnostic;
 use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
-use std::fs;
 use std::process::{Command, Stdio};
 use std::collections::HashMap;
-use strum::IntoEnumIterator;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
 
 
 // To define what stage the frontend developer is at
@@ -46,10 +54,11 @@ pub struct APIAssignment {
 type PageRoutes = HashMap<String, Vec<APIAssignment>>;
 
 
-// Used for decoding page names and suggested content
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PageAPIAssign {
-  pub page: Vec<APIAssignment>
+// Errors from a component test, along with the frontend file to fix
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentFailure {
+  pub file_path: String,
+  pub errors: String,
 }
 
 
@@ -75,20 +84,21 @@ pub struct DesignBuildSheet {
 #[derive(Debug)]
 pub struct AgentFrontendDeveloper {
   pub attributes: BasicAgent,
+  pub workspace: Workspace,
+  pub sandbox: Arc<dyn Sandbox>,
   pub buildsheet: DesignBuildSheet,
   pub bug_count: u8,
-  pub operation_focus: BuildComponent
+  pub operation_focus: BuildComponent,
+  pub selection: ComponentSelection
 }
 
 impl AgentFrontendDeveloper {
-  pub fn new() -> Self {
+  pub fn new(workspace: Workspace, sandbox: Arc<dyn Sandbox>, selection: ComponentSelection) -> Self {
 
     // Define attributes
     let attributes: BasicAgent = BasicAgent {
-      objective: "Develops frontned code for website".to_string(),
       position: "Frontend Developer".to_string(),
-      state: AgentState::Discovery,
-      memory: vec![]
+      state: AgentState::Discovery
     };
 
     // Define Buildsheet
@@ -103,9 +113,12 @@ impl AgentFrontendDeveloper {
     // Return Self
     Self {
       attributes,
+      workspace,
+      sandbox,
       buildsheet,
       bug_count: 0,
-      operation_focus: BuildComponent::Logo
+      operation_focus: BuildComponent::Logo,
+      selection
     }
   }
 
@@ -122,9 +135,8 @@ impl AgentFrontendDeveloper {
   // Get pages and page context from description and backend code
   async fn get_page_context(&mut self, project_description: &String) {
 
-    // Extract backend code
-    let path: String = format!("{}/src/main.rs", BACKEND_CODE_DIR);
-    let backend_code: String = fs::read_to_string(path).expect("Something went wrong reading the file");
+    // Extract backend code across all of its files
+    let backend_code: String = combine_sources(&read_backend_sources(&self.workspace));
 
     // Structure Message
     let msg_context: String = format!("PROJECT_DESCRIPTION: {:?}, CODE_LOGIC: {:?}", project_description, backend_code);
@@ -138,7 +150,7 @@ impl AgentFrontendDeveloper {
 
     // Extract pages
     let pages: Vec<String> = ai_response
-      .iter().filter_map(|item| Some(item.page_name.clone())).collect();
+      .iter().map(|item| item.page_name.clone()).collect();
 
     // Assign pages to buildsheet
     self.buildsheet.pages = Some(pages.clone());
@@ -150,8 +162,7 @@ impl AgentFrontendDeveloper {
   async fn assign_api_routes(&mut self, project_description: &String, external_api_urls: &Option<Vec<String>>) {
 
     // Extract internal API schema
-    let path: String = format!("{}/api_endpoints.json", BACKEND_CODE_DIR);
-    let internal_api_endpoints: String = fs::read_to_string(path).expect("Something went wrong reading the file");
+    let internal_api_endpoints: String = read_openapi_spec(&self.workspace);
 
     // Extract external API endpoints
     let external_api_endpoints: String = match external_api_urls {
@@ -204,7 +215,7 @@ impl AgentFrontendDeveloper {
     // Initialize
     PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), 
       "Fixing component bugs");
-    let buggy_code: String = read_frontend_code_contents(&file_path);
+    let buggy_code: String = read_frontend_code_contents(&self.workspace, &file_path);
 
     // Structure message
     let msg_context: String = format!("ORIGINAL_CODE: {}, ERROR_MESSAGE: {:?}", buggy_code, error_code);
@@ -216,49 +227,148 @@ impl AgentFrontendDeveloper {
       get_function_string!(print_code_bugs_resolution), 
       print_code_bugs_resolution).await;
 
-    // Save corrected code
-    save_frontend_code(&file_path, &ai_response);
+    // Save and commit corrected code
+    save_frontend_code(&self.workspace, &file_path, &ai_response);
+    record_step(&self.workspace, &self.attributes.position, get_function_string!(print_code_bugs_resolution), &self.attributes.state);
   }
 
 
-  // Frontend component test
-  async fn perform_component_test(&mut self) -> Result<(), String> {
+  // Frontend component test, giving the failure to fix, or an error when the frontend cannot be checked at all
+  async fn perform_component_test(&mut self) -> Result<Option<ComponentFailure>, Box<dyn std::error::Error + Send>> {
     let test_statement = format!("Testing Component: {}", self.operation_focus.name());
     PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), test_statement.as_str());
-    let build_frontend_server: std::process::Output = Command::new("yarn")
-      .arg("build")
-      .current_dir(FRONTEND_CODE_DIR)
-      .stdout(Stdio::piped())
-      .stderr(Stdio::piped())
-      .output()
-      .expect("Failed to run component test");
 
-    // Determine if build errors
-    if build_frontend_server.status.success() {
-      PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Component build test successful");
-      self.bug_count = 0;
-      return Ok(());
+    // Install packages on the host when they are missing or out of date, so the sandboxed build can run offline
+    if is_install_stale(&self.workspace.frontend_dir) {
+      PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Installing frontend packages...");
+      let install_output: std::process::Output = match Command::new("yarn")
+        .arg("install")
+        .current_dir(&self.workspace.frontend_dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output() {
+        Ok(install_output) => install_output,
+        Err(e) => return Err(Box::new(std::io::Error::other(format!("Failed to run yarn install: {}", e)))),
+      };
+      if !install_output.status.success() {
+        let install_err: String = format!("Failed to install frontend packages ({}): {}", install_output.status, String::from_utf8_lossy(&install_output.stderr).trim());
+        PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), install_err.as_str());
+        return Err(Box::new(std::io::Error::other(install_err)));
+      }
+      mark_installed(&self.workspace.frontend_dir);
+    }
 
-    // Handle Build error
-    } else {
-      let error_arr: Vec<u8> = build_frontend_server.stderr;
-      let error_str: String = String::from_utf8(error_arr).unwrap();
+    // Type check and lint inside the sandbox, keeping each error's file
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    for gate in FrontendGate::all() {
+      let gate_output: std::process::Output = match run_sandboxed(self.sandbox.as_ref(), &gate.command(&self.workspace.frontend_dir)) {
+        Ok(gate_output) => gate_output,
+        Err(e) => return Err(Box::new(std::io::Error::other(format!("Failed to run {:?} on the frontend: {}", gate, e)))),
+      };
+      diagnostics.extend(gate.diagnostics(&gate_output, &self.workspace.frontend_dir));
+    }
+    for diagnostic in &diagnostics {
+      PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), diagnostic.to_string().as_str());
+    }
+
+    // A gate failing without naming any file is a broken toolchain, which rewriting the component will not fix
+    if !diagnostics.is_empty() && diagnostics.iter().all(|diagnostic| diagnostic.file.is_empty()) {
+      let gate_output: String = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<&str>>().join("\n");
+      return Err(Box::new(std::io::Error::other(format!("Frontend checks failed without naming a file: {}", gate_output))));
+    }
+
+    // Build frontend inside the sandbox once it type checks and lints cleanly
+    let failure: Option<ComponentFailure> = match diagnostics.is_empty() {
+      false => Some(component_failure(&diagnostics, &self.operation_focus.filepath())),
+      true => {
+        let build_command: SandboxCommand = SandboxCommand::new("yarn", &["build"], &self.workspace.frontend_dir);
+        let build_frontend_server: std::process::Output = match run_sandboxed(self.sandbox.as_ref(), &build_command) {
+          Ok(build_frontend_server) => build_frontend_server,
+          Err(e) => return Err(Box::new(std::io::Error::other(format!("Failed to build the frontend: {}", e)))),
+        };
+        let build_output: String = format!("{}{}",
+          String::from_utf8_lossy(&build_frontend_server.stdout),
+          String::from_utf8_lossy(&build_frontend_server.stderr));
+        (!build_frontend_server.status.success()).then(|| ComponentFailure {
+          file_path: failing_file(&build_output).unwrap_or(self.operation_focus.filepath()),
+          errors: format!("BUILD_ERRORS: {}", build_output)
+        })
+      }
+    };
+
+    // Determine if build errors
+    if let Some(failure) = failure {
 
       // Check and return error
       self.bug_count += 1;
       if self.bug_count >= 2 {
         PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), "Too many code failures");
         PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), "Remember: check frontend builds before retrying");
-        panic!("Too many code failed attempts for {}", self.operation_focus.name());
+        Err(Box::new(std::io::Error::other(format!("Too many code failed attempts for {}", self.operation_focus.name()))))
       } else {
-        return Err(error_str)
+        Ok(Some(failure))
       }
+    } else {
+      PrintCommand::UnitTest.print_agent_message(self.attributes.position.as_str(), "Component build test successful");
+      self.bug_count = 0;
+      Ok(None)
     }
   }
 
 }
 
 
+// Packages need installing when node_modules is missing or older than package.json or yarn.lock
+fn is_install_stale(frontend_dir: &Path) -> bool {
+  let modified_at = |file_name: &str| -> Option<SystemTime> {
+    fs::metadata(frontend_dir.join(file_name)).and_then(|metadata| metadata.modified()).ok()
+  };
+  let Some(installed_at) = modified_at("node_modules") else {
+    return true;
+  };
+  ["package.json", "yarn.lock"].iter()
+    .filter_map(|file_name| modified_at(file_name))
+    .any(|changed_at| changed_at > installed_at)
+}
+
+
+// Stamps node_modules as installed now, as yarn leaves it untouched when nothing needed changing
+fn mark_installed(frontend_dir: &Path) {
+  let _ = fs::File::open(frontend_dir.join("node_modules")).and_then(|node_modules| node_modules.set_modified(SystemTime::now()));
+}
+
+
+// Type check and lint errors to fix, preferring those in the component's own file
+// Errors elsewhere, such as a page the app shell imports, are fixed in the first file they are found in
+fn component_failure(diagnostics: &[Diagnostic], component_path: &str) -> ComponentFailure {
+  let component_file: &str = component_path.trim_start_matches('/');
+  let file: &str = match diagnostics.iter().any(|diagnostic| diagnostic.file == component_file) {
+    true => component_file,
+    false => diagnostics.first().map(|diagnostic| diagnostic.file.as_str()).unwrap_or(component_file)
+  };
+  let file_diagnostics: Vec<&Diagnostic> = diagnostics.iter().filter(|diagnostic| diagnostic.file == file).collect();
+  ComponentFailure {
+    file_path: match file.is_empty() {
+      true => component_path.to_string(),
+      false => format!("/{}", file)
+    },
+    errors: format!("TYPE_CHECK_AND_LINT_ERRORS: {}", serde_json::to_string_pretty(&file_diagnostics).expect("Failed to encode diagnostics"))
+  }
+}
+
+
+// Frontend file a build error is reported in, such as "/src/components/pages/HomePage.tsx" for
+// "src/components/pages/HomePage.tsx(12,5): error TS2304: Cannot find name 'x'."
+fn failing_file(build_output: &str) -> Option<String> {
+  build_output.lines().find_map(|line| {
+    let start: usize = line.rfind("src/")?;
+    let end: usize = line[start..].find(|c: char| "(:\"'".contains(c) || c.is_whitespace()).unwrap_or(line.len() - start) + start;
+    let path: &str = &line[start..end];
+    (path.ends_with(".ts") || path.ends_with(".tsx")).then(|| format!("/{}", path))
+  })
+}
+
+
 #[async_trait]
 impl SpecialFunctions for AgentFrontendDeveloper {
 
@@ -266,7 +376,17 @@ impl SpecialFunctions for AgentFrontendDeveloper {
     &self.attributes
   }
 
-  async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error>> {
+  // Builds pages around the backend endpoints and brand colours
+  fn reads(&self) -> Vec<FactSheetField> {
+    vec![FactSheetField::ProjectDescription, FactSheetField::ApiEndpointSchema, FactSheetField::BrandColours]
+  }
+
+  // Frontend is the last stop, nothing is handed on
+  fn produces(&self) -> Vec<FactSheetField> {
+    vec![]
+  }
+
+  async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error + Send>> {
 
     // Extract required project factsheet items
     let project_description: &String = &factsheet.project_description;
@@ -286,13 +406,16 @@ impl SpecialFunctions for AgentFrontendDeveloper {
           self.confirm_stage();
 
           // Get pages and page context
-          self.get_page_context(&project_description).await;
+          self.get_page_context(project_description).await;
 
           // Assign API routes to pages
-          self.assign_api_routes(&project_description, &external_api_urls).await;
+          self.assign_api_routes(project_description, external_api_urls).await;
 
-          // Define Brand Colours
-          self.define_brand_colours(&project_description).await;
+          // Use Brand Colours from Brand Designer if already defined
+          match &factsheet.brand_colours {
+            Some(brand_colours) => self.buildsheet.brand_colours = Some(brand_colours.clone()),
+            None => self.define_brand_colours(project_description).await
+          }
 
           // Proceed to Working status
           self.attributes.state = AgentState::Working;
@@ -302,37 +425,41 @@ impl SpecialFunctions for AgentFrontendDeveloper {
         // Get pages, api assignments and branding
         AgentState::Working => {
           
-          // Loop through components
-          for component in BuildComponent::iter() {
-            
+          // Loop through shared components, then every page
+          let pages: Vec<String> = match self.buildsheet.pages.clone() {
+            Some(pages) => pages,
+            None => return Err(Box::new(std::io::Error::other("Must have the site's pages before building the frontend"))),
+          };
+          let components: Vec<BuildComponent> = BuildComponent::build_order(&pages);
+          for name in self.selection.unmatched(&components) {
+            let unmatched_msg: String = format!("No component named {}, the components are {:?}", name,
+              components.iter().map(BuildComponent::name).collect::<Vec<String>>());
+            PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), unmatched_msg.as_str());
+          }
+          for component in components {
 
-            // !!!! REMOVE ONLY FOR TESTING !!!
-            if component != BuildComponent::PageContent1 {
+            // Leave components which were not selected as they are
+            if !self.selection.includes(&component) {
               continue;
             }
-            if component == BuildComponent::PageContent2 {
-              break;
-            }
-
 
             // Update current operation focus to component
             self.operation_focus = component.clone();
-            component.create_component(&self, &project_description).await;
+            component.create_component(&self, project_description).await;
 
             // Unit test component
-            let test_res: Result<(), String> = self.perform_component_test().await;
+            let test_res: Option<ComponentFailure> = self.perform_component_test().await?;
             match test_res {
 
               // Continue to next component
-              Ok(()) => continue,
+              None => continue,
 
-              // Fix bugs for current component
-              Err(err_str) => {
-                let file_path: String = self.operation_focus.filepath();
-                self.run_code_correction(file_path, err_str).await;
+              // Fix bugs in the file the build failed on, which is not always the current component once the app shell routes every page
+              Some(failure) => {
+                self.run_code_correction(failure.file_path, failure.errors).await;
 
                 // Perform one more test
-                let _ = self.perform_component_test().await;
+                self.perform_component_test().await?;
                 continue;
               }
             }
@@ -355,13 +482,94 @@ impl SpecialFunctions for AgentFrontendDeveloper {
 #[cfg(test)]
 pub mod tests {
   use super::*;
+  use crate::helpers::sandbox::detect_sandbox;
+  use crate::helpers::general::{read_code_template_contents, save_openapi_spec};
+  use crate::helpers::openapi::openapi_document;
+  use crate::helpers::routes::extract_routes;
+  use crate::helpers::scaffold::scaffold_project;
+
+  // Scaffolded project in the temp folder, with the OpenAPI document the backend would leave behind
+  fn frontend_workspace(label: &str) -> Workspace {
+    let workspace: Workspace = Workspace::temporary(label);
+    scaffold_project(&workspace).expect("Failed to scaffold project");
+    let routes = extract_routes(&read_code_template_contents(&workspace)).expect("Failed to extract routes");
+    save_openapi_spec(&workspace, &openapi_document("Fitness tracker", &routes));
+    workspace
+  }
+
+  #[test]
+  fn reinstalls_when_manifest_changes() {
+    let dir: std::path::PathBuf = std::env::temp_dir().join(format!("gippity-install-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("package.json"), "{}").unwrap();
+    let is_missing_stale: bool = is_install_stale(&dir);
+
+    fs::create_dir_all(dir.join("node_modules")).unwrap();
+    mark_installed(&dir);
+    let is_fresh_stale: bool = is_install_stale(&dir);
+
+    let later: SystemTime = SystemTime::now() + std::time::Duration::from_secs(5);
+    fs::File::options().write(true).open(dir.join("package.json")).unwrap().set_modified(later).unwrap();
+    let is_changed_stale: bool = is_install_stale(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(is_missing_stale);
+    assert!(!is_fresh_stale);
+    assert!(is_changed_stale);
+  }
+
+  #[tokio::test]
+  async fn fails_when_packages_do_not_install() {
+    let workspace: Workspace = Workspace::temporary("broken-install");
+    fs::create_dir_all(&workspace.frontend_dir).unwrap();
+    fs::write(workspace.frontend_path("package.json"), "{ not json").unwrap();
+    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new(workspace.clone(), detect_sandbox(true).unwrap(), ComponentSelection::default());
+    let test_res = agent.perform_component_test().await;
+    fs::remove_dir_all(&workspace.root).unwrap();
+
+    assert!(test_res.is_err());
+    assert_eq!(agent.bug_count, 0);
+  }
+
+  #[test]
+  fn finds_file_build_failed_on() {
+    let tsc_output: &str = "$ tsc && vite build\nsrc/components/pages/HomePage.tsx(12,5): error TS2304: Cannot find name 'x'.\nerror Command failed with exit code 2.";
+    assert_eq!(failing_file(tsc_output), Some("/src/components/pages/HomePage.tsx".to_string()));
+    let vite_output: &str = "error during build:\nRollup failed to resolve import \"x\" from \"/tmp/env/web/src/components/Controller.tsx\".";
+    assert_eq!(failing_file(vite_output), Some("/src/components/Controller.tsx".to_string()));
+    assert_eq!(failing_file("error Command failed with exit code 1."), None);
+  }
+
+  #[test]
+  fn ties_errors_to_component_file() {
+    let diagnostic = |file: &str, message: &str| Diagnostic { file: file.to_string(), line: 1, code: None, message: message.to_string() };
+    let diagnostics: Vec<Diagnostic> = vec![
+      diagnostic("src/components/pages/AboutPage.tsx", "unused import"),
+      diagnostic("src/components/pages/HomePage.tsx", "missing type"),
+    ];
+    let failure: ComponentFailure = component_failure(&diagnostics, "/src/components/pages/HomePage.tsx");
+    assert_eq!(failure.file_path, "/src/components/pages/HomePage.tsx");
+    assert!(failure.errors.contains("missing type") && !failure.errors.contains("unused import"));
+
+    let failure: ComponentFailure = component_failure(&diagnostics, "/src/components/Controller.tsx");
+    assert_eq!(failure.file_path, "/src/components/pages/AboutPage.tsx");
+    assert_eq!(component_failure(&[diagnostic("", "tsc crashed")], "/src/components/Controller.tsx").file_path, "/src/components/Controller.tsx");
+  }
+
 
+  #[tokio::test]
+  async fn fails_without_pages() {
+    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new(Workspace::temporary("without-pages"), detect_sandbox(true).unwrap(), ComponentSelection::default());
+    agent.attributes.state = AgentState::Working;
+    let mut factsheet: FactSheet = serde_json::from_str("{\"project_description\":\"Build a todo app\",\"project_scope\":null,\"external_urls\":null,\"backend_code\":null,\"api_endpoint_schema\":null}").unwrap();
+    assert!(agent.execute(&mut factsheet).await.is_err());
+  }
 
   #[tokio::test]
   async fn develops_context_and_branding() {
 
     // Create agent instance and site purpose
-    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new();
+    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new(frontend_workspace("context-and-branding"), detect_sandbox(true).unwrap(), ComponentSelection::default());
 
     // Initialze Factsheet
     let mut factsheet: FactSheet = serde_json::from_str("{\"project_description\":\"Build a todo app for a fitness tracking goal\",\"project_scope\":{\"is_crud_required\":true,\"is_user_login_and_logout\":true,\"is_external_urls_required\":true},\"external_urls\":[\"https://api.exchangeratesapi.io/latest\"],\"backend_code\":null,\"frontend_code\":null,\"json_db_schema\":null}").unwrap();
@@ -375,7 +583,7 @@ pub mod tests {
   async fn works_on_shared_components() {
 
     // Create agent instance and site purpose
-    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new();
+    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new(frontend_workspace("shared-components"), detect_sandbox(true).unwrap(), ComponentSelection::default());
     agent.attributes.state = AgentState::Working;
     agent.buildsheet.pages = Some(vec!["home_page".to_string(), "about_page".to_string()]);
 
@@ -391,7 +599,7 @@ pub mod tests {
   async fn works_on_final_pages() {
 
     // Create agent instance and site purpose
-    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new();
+    let mut agent: AgentFrontendDeveloper = AgentFrontendDeveloper::new(frontend_workspace("final-pages"), detect_sandbox(true).unwrap(), ComponentSelection::default());
     let factsheet_str: &str = "{\"project_description\":\"build a website that fetches and tracks fitness progress with timezone information\",\"project_scope\":{\"is_crud_required\":true,\"is_user_login_and_logout\":true,\"is_external_urls_required\":true},\"external_urls\":[\"https://ipapi.co/json\",\"https://wger.de/api/v2/\"],\"backend_code\":\"use actix_cors::Cors;\\nuse actix_web::{http::header, web, App, HttpServer, Responder, HttpResponse};\\nuse serde::{Deserialize, Serialize};\\nuse std::sync::Mutex;\\nuse std::collections::HashMap;\\nuse std::fs;\\nuse std::io::Write;\\nuse reqwest::Client as HttpClient;\\nuse async_trait::async_trait;\\n\\n#[derive(Serialize, Deserialize, Debug, Clone)]\\npub struct FitnessProgress {\\n    pub id: u64,\\n    pub user_id: u64,\\n    pub progress_data: String,\\n    pub timezone: String,\\n}\\n\\n#[derive(Serialize, Deserialize, Debug, Clone)]\\npub struct User {\\n    pub id: u64,\\n    pub username: String,\\n    pub password: String,\\n}\\n\\n#[derive(Serialize, Deserialize, Debug, Clone)]\\nstruct Database {\\n    fitness_progresses: HashMap<u64, FitnessProgress>,\\n    users: HashMap<u64, User>,\\n}\\n\\nimpl Database {\\n    fn new() -> Self {\\n        Self {\\n            fitness_progresses: HashMap::new(),\\n            users: HashMap::new(),\\n        }\\n    }\\n\\n    // FITNESS_PROGRESS CRUD OPERATIONS\\n    fn insert_progress(&mut self, progress: FitnessProgress) {\\n        self.fitness_progresses.insert(progress.id, progress);\\n    }\\n\\n    fn get_progress(&self, id: &u64) -> Option<&FitnessProgress> {\\n        self.fitness_progresses.get(id)\\n    }\\n\\n    fn get_all_progresses(&self) -> Vec<&FitnessProgress> {\\n        self.fitness_progresses.values().collect()\\n    }\\n\\n    fn delete_progress(&mut self, id: &u64) {\\n        self.fitness_progresses.remove(id);\\n    }\\n\\n    fn update_progress(&mut self, progress: FitnessProgress) {\\n        self.fitness_progresses.insert(progress.id, progress);\\n    }\\n\\n    // USER DATA RELATED OPERATIONS\\n    fn insert_user(&mut self, user: User) {\\n        self.users.insert(user.id, user);\\n    }\\n\\n    fn get_user_by_name(&self, username: &str) -> Option<&User> {\\n        self.users.values().find(|u| u.username == username)\\n    }\\n\\n    // DATABASE SAVING\\n    fn save_to_file(&self) -> std::io::Result<()> {\\n        let data = serde_json::to_string(&self)?;\\n        let mut file = fs::File::create(\\\"database.json\\\")?;\\n        file.write_all(data.as_bytes())?;\\n        Ok(())\\n    }\\n\\n    fn load_from_file() -> std::io::Result<Self> {\\n        let file_content = fs::read_to_string(\\\"database.json\\\")?;\\n        let db: Database = serde_json::from_str(&file_content)?;\\n        Ok(db)\\n    }\\n}\\n\\nstruct AppState {\\n    db: Mutex<Database>,\\n    http_client: HttpClient,\\n}\\n\\n#[async_trait]\\ntrait ExternalDataFetcher {\\n    async fn fetch_external_data(&self, url: &str) -> Result<String, reqwest::Error>;\\n}\\n\\n#[async_trait]\\nimpl ExternalDataFetcher for AppState {\\n    async fn fetch_external_data(&self, url: &str) -> Result<String, reqwest::Error> {\\n        let response = self.http_client.get(url).send().await?;\\n        let content = response.text().await?;\\n        Ok(content)\\n    }\\n}\\n\\nasync fn create_progress(\\n    app_state: web::Data<AppState>,\\n    progress: web::Json<FitnessProgress>,\\n) -> impl Responder {\\n    let mut db = app_state.db.lock().unwrap();\\n    db.insert_progress(progress.into_inner());\\n    let _ = db.save_to_file();\\n    HttpResponse::Ok().finish()\\n}\\n\\nasync fn read_progress(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {\\n    let db = app_state.db.lock().unwrap();\\n    match db.get_progress(&id.into_inner()) {\\n        Some(progress) => HttpResponse::Ok().json(progress),\\n        None => HttpResponse::NotFound().finish(),\\n    }\\n}\\n\\nasync fn read_all_progresses(app_state: web::Data<AppState>) -> impl Responder {\\n    let db = app_state.db.lock().unwrap();\\n    let progresses = db.get_all_progresses();\\n    HttpResponse::Ok().json(progresses)\\n}\\n\\nasync fn update_progress(\\n    app_state: web::Data<AppState>,\\n    progress: web::Json<FitnessProgress>,\\n) -> impl Responder {\\n    let mut db = app_state.db.lock().unwrap();\\n    db.update_progress(progress.into_inner());\\n    let _ = db.save_to_file();\\n    HttpResponse::Ok().finish()\\n}\\n\\nasync fn delete_progress(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {\\n    let mut db = app_state.db.lock().unwrap();\\n    db.delete_progress(&id.into_inner());\\n    let _ = db.save_to_file();\\n    HttpResponse::Ok().finish()\\n}\\n\\nasync fn register(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {\\n    let mut db = app_state.db.lock().unwrap();\\n    db.insert_user(user.into_inner());\\n    let _ = db.save_to_file();\\n    HttpResponse::Ok().finish()\\n}\\n\\nasync fn login(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {\\n    let db = app_state.db.lock().unwrap();\\n\\n    match db.get_user_by_name(&user.username) {\\n        Some(stored_user) if stored_user.password == user.password => {\\n            HttpResponse::Ok().body(\\\"Logged in!\\\")\\n        }\\n        _ => HttpResponse::BadRequest().body(\\\"Invalid username or password\\\"),\\n    }\\n}\\n\\n#[actix_web::main]\\nasync fn main() -> std::io::Result<()> {\\n    let db = match Database::load_from_file() {\\n        Ok(db) => db,\\n        Err(_) => Database::new(),\\n    };\\n\\n    let data = web::Data::new(AppState {\\n        db: Mutex::new(db),\\n        http_client: HttpClient::new(),\\n    });\\n\\n    HttpServer::new(move || {\\n        App::new()\\n            .wrap(\\n                Cors::permissive()\\n                    .allowed_origin_fn(|origin, _req_head| {\\n                        origin.as_bytes().starts_with(b\\\"http://localhost:\\\") || origin == \\\"null\\\"\\n                    })\\n                    .allowed_methods(vec![\\\"GET\\\", \\\"POST\\\", \\\"PUT\\\", \\\"DELETE\\\"])\\n                    .allowed_headers(vec![header::AUTHORIZATION, header::ACCEPT])\\n                    .allowed_header(header::CONTENT_TYPE)\\n                    .supports_credentials()\\n                    .max_age(3600),\\n            )\\n            .app_data(data.clone())\\n            .route(\\\"/progress\\\", web::post().to(create_progress))\\n            .route(\\\"/progress\\\", web::get().to(read_all_progresses))\\n            .route(\\\"/progress/{id}\\\", web::get().to(read_progress))\\n            .route(\\\"/progress/{id}\\\", web::put().to(update_progress))\\n            .route(\\\"/progress/{id}\\\", web::delete().to(delete_progress))\\n            .route(\\\"/register\\\", web::post().to(register))\\n            .route(\\\"/login\\\", web::post().to(login))\\n    })\\n    .bind(\\\"127.0.0.1:8080\\\")?\\n    .run()\\n    .await\\n}\",\"api_endpoint_schema\":[{\"is_route_dynamic\":\"false\",\"method\":\"get\",\"request_body\":\"None\",\"response\":\"Array\",\"route\":\"/progress\"}]}";
     let buildsheet_str: &str = "{\"pages\":[\"home_page\",\"progress_dashboard\"],\"pages_descriptons\":[{\"page_name\":\"home_page\",\"suggested_content_sections\":{\"banner_section\":\"Catchy title and subtitle showcasing the fitness progress tracking features\",\"call_to_action_section\":\"Encourage users to sign up and start tracking their fitness progress\",\"features_section\":\"Display key features of the website with icons and short descriptions\"}},{\"page_name\":\"progress_dashboard\",\"suggested_content_sections\":{\"add_progress_section\":\"Provide a form for the user to input new fitness progress data\",\"fitness_progress_section\":\"Display a visual representation of the user's fitness progress over time\",\"user_info_section\":\"Display user's name, timezone info and greetings based on the time of the day\"}}],\"api_assignments\":{\"home_page\":[{\"api_route\":\"/register\",\"method\":\"post\",\"route_type\":\"internal\"},{\"api_route\":\"/login\",\"method\":\"post\",\"route_type\":\"internal\"},{\"api_route\":\"https://ipapi.co/json\",\"method\":\"get\",\"route_type\":\"external\"}],\"progress_dashboard\":[{\"api_route\":\"/progress\",\"method\":\"post\",\"route_type\":\"internal\"},{\"api_route\":\"/progress\",\"method\":\"get\",\"route_type\":\"internal\"},{\"api_route\":\"/progress/{id}\",\"method\":\"get\",\"route_type\":\"internal\"},{\"api_route\":\"/progress/{id}\",\"method\":\"put\",\"route_type\":\"internal\"},{\"api_route\":\"/progress/{id}\",\"method\":\"delete\",\"route_type\":\"internal\"},{\"api_route\":\"https://wger.de/api/v2/\",\"method\":\"get\",\"route_type\":\"external\"}]},\"brand_colours\":[\"#32a852\",\"#0fa0d1\",\"#d10fcb\"],\"build_mode\":\"Infrastructure\"}";
     let mut factsheet: FactSheet = serde_json::from_str(factsheet_str).unwrap();
@@ -3,71 +3,128 @@ use crate::ai_functions::aifunc_frontend::{
   print_completed_logo_with_brand_name_react_component,
   print_header_navigation_react_component,
   print_footer_navigation_react_component,
-  print_react_typescript_hook_component,
   print_html_webpage_content_with_text,
-  print_create_react_component_with_API_integration,
+  print_create_react_component_with_api_integration,
   print_create_full_react_component,
   print_give_component_fantastic_styling
 };
+use crate::helpers::api_client::{generate_api_client, ApiClient, API_CLIENT_PATH, API_TYPES_PATH};
+use crate::helpers::app_shell::{generate_app_shell, page_component_names, AppShell, CONTROLLER_PATH, NOT_FOUND_PATH, PAGE_LINKS_PATH};
+use crate::helpers::git::record_step;
 use crate::helpers::general::{
   save_frontend_code, 
   ai_task_request, 
   read_frontend_code_contents,
-  BACKEND_CODE_DIR, 
-  FRONTEND_CODE_DIR
+  read_openapi_spec
 };
 use crate::models::agents::agent_frontend::AgentFrontendDeveloper;
 use serde::{Serialize, Deserialize};
-use std::fs;
-use strum_macros::EnumIter;
 
 // Used for decoding page names and suggested content
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, EnumIter)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum BuildComponent {
   Logo,
   NavHeader,
   NavFooter,
-  ReactHook,
-  PageContent1,
-  PageContent2
+  ApiClient,
+  PageContent { page_name: String, component_name: String },
+  AppShell
 }
 
+// Components to build, such as --only Logo,NavHeader or --skip ApiClient
+// Names match a component such as HomePage or its kind such as PageContent, and everything is built by default
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComponentSelection {
+  pub only: Vec<String>,
+  pub skip: Vec<String>,
+}
+
+impl ComponentSelection {
+
+  // Names given which no component matches
+  pub fn unmatched(&self, components: &[BuildComponent]) -> Vec<String> {
+    self.only.iter().chain(self.skip.iter())
+      .filter(|name| !components.iter().any(|component| Self::matches(name, component)))
+      .cloned()
+      .collect()
+  }
+
+  // Whether a component is built this run
+  pub fn includes(&self, component: &BuildComponent) -> bool {
+    let is_listed = |names: &Vec<String>| names.iter().any(|name| Self::matches(name, component));
+    (self.only.is_empty() || is_listed(&self.only)) && !is_listed(&self.skip)
+  }
+
+  fn matches(name: &str, component: &BuildComponent) -> bool {
+    name.eq_ignore_ascii_case(component.kind()) || name.eq_ignore_ascii_case(&component.name())
+  }
+}
+
+
 impl BuildComponent {
 
-  pub fn name(&self) -> &'static str {
-    match *self {
+  // Shared components first, then one component per page and the app shell routing to them
+  pub fn build_order(pages: &[String]) -> Vec<BuildComponent> {
+    let mut components: Vec<BuildComponent> = vec![
+      BuildComponent::Logo,
+      BuildComponent::NavHeader,
+      BuildComponent::NavFooter,
+      BuildComponent::ApiClient
+    ];
+    for (page_name, component_name) in pages.iter().zip(page_component_names(pages)) {
+      components.push(BuildComponent::PageContent { page_name: page_name.clone(), component_name });
+    }
+    components.push(BuildComponent::AppShell);
+    components
+  }
+
+  // Kind of component, shared by every page
+  pub fn kind(&self) -> &'static str {
+    match self {
       BuildComponent::Logo => "Logo",
       BuildComponent::NavHeader => "NavHeader",
       BuildComponent::NavFooter => "NavFooter",
-      BuildComponent::ReactHook => "ReactHook",
-      BuildComponent::PageContent1 => "PageContent1",
-      BuildComponent::PageContent2 => "PageContent2"
+      BuildComponent::ApiClient => "ApiClient",
+      BuildComponent::PageContent { .. } => "PageContent",
+      BuildComponent::AppShell => "AppShell"
+    }
+  }
+
+  pub fn name(&self) -> String {
+    match self {
+      BuildComponent::Logo => "Logo".to_string(),
+      BuildComponent::NavHeader => "NavHeader".to_string(),
+      BuildComponent::NavFooter => "NavFooter".to_string(),
+      BuildComponent::ApiClient => "ApiClient".to_string(),
+      BuildComponent::PageContent { component_name, .. } => component_name.clone(),
+      BuildComponent::AppShell => "AppShell".to_string()
     }
   }
 
   pub fn filepath(&self) -> String {
-    match *self {
+    match self {
       BuildComponent::Logo => "/src/components/shared/Logo.tsx".to_string(),
       BuildComponent::NavHeader => "/src/components/shared/Navigation.tsx".to_string(),
       BuildComponent::NavFooter => "/src/components/shared/Footer.tsx".to_string(),
-      BuildComponent::ReactHook => "/src/hooks/useCall.tsx".to_string(),
-      BuildComponent::PageContent1 => "/src/components/pages/PageOne.tsx".to_string(),
-      BuildComponent::PageContent2 => "/src/components/pages/PageTwo.tsx".to_string()
+      BuildComponent::ApiClient => API_CLIENT_PATH.to_string(),
+      BuildComponent::PageContent { component_name, .. } => format!("/src/components/pages/{}.tsx", component_name),
+      BuildComponent::AppShell => CONTROLLER_PATH.to_string()
     }
   }
 
   // Create component
-  async fn create_and_save(&self, msg_context: String,  ai_function: fn(&str) -> &'static str) {
+  async fn create_and_save(&self, agent: &AgentFrontendDeveloper, msg_context: String, ai_func_name: &str, ai_function: fn(&str) -> &'static str) {
 
     // Retrieve AI Reponse
     let ai_response: String = ai_task_request(
       msg_context, 
       "Component Writer",
-      &self.name(), 
+      ai_func_name, 
       ai_function).await;
 
-    // Save Component
-    save_frontend_code(&self.filepath(), &ai_response);
+    // Save and commit Component
+    save_frontend_code(&agent.workspace, &self.filepath(), &ai_response);
+    record_step(&agent.workspace, &agent.attributes.position, ai_func_name, &agent.attributes.state);
   }
 
   // Prepare and create component
@@ -99,7 +156,9 @@ impl BuildComponent {
 
         // Create Component
         self.create_and_save(
+          agent,
           msg_context,
+          get_function_string!(print_completed_logo_with_brand_name_react_component),
           print_completed_logo_with_brand_name_react_component
         ).await;
       },
@@ -114,49 +173,48 @@ impl BuildComponent {
           }}", project_description, pages, agent.buildsheet.brand_colours);
 
         // Create and Save
-        if self.name() == "NavHeader" {
+        if *self == BuildComponent::NavHeader {
           self.create_and_save(
+            agent,
             msg_context,
+            get_function_string!(print_header_navigation_react_component),
             print_header_navigation_react_component).await;
         } else {
           self.create_and_save(
+            agent,
             msg_context,
+            get_function_string!(print_footer_navigation_react_component),
             print_footer_navigation_react_component).await;
         }
       },
 
-      Self::ReactHook => {
+      Self::ApiClient => {
 
-        // Initialize
-        let path: String = format!("{}/api_endpoints.json", BACKEND_CODE_DIR);
-        let api_endpoints: String = fs::read_to_string(path).expect("Something went wrong reading the file");
-        
-        // Create and Save
-        self.create_and_save(
-          format!("API_ENDPOINTS_JSON_SCHEMA: {}", api_endpoints),
-          print_react_typescript_hook_component
-        ).await;
-      },
+        // Generate typed client from the backend's OpenAPI document
+        let openapi_spec: serde_json::Value = serde_json::from_str(&read_openapi_spec(&agent.workspace))
+          .expect("Failed to decode OpenAPI document");
+        let api_client: ApiClient = generate_api_client(&openapi_spec);
 
-      Self::PageContent1 | Self::PageContent2 => {
+        // Save and commit client
+        save_frontend_code(&agent.workspace, API_TYPES_PATH, &api_client.types);
+        save_frontend_code(&agent.workspace, API_CLIENT_PATH, &api_client.client);
+        record_step(&agent.workspace, &agent.attributes.position, "generate_api_client", &agent.attributes.state);
+      },
 
-        // Extract page name
-        let (page_name, page_index) = match self.name(){
-          "PageContent1" => (&pages[0], 0),
-          "PageContent2" => (&pages[1], 1),
-          _ => panic!("Page not recognised")
-        };
+      Self::PageContent { page_name, component_name } => {
 
         // Extract page input information
-        let file_path: String = self.filepath();
-        dbg!(&file_path);
-        let react_hook_contents: String = read_frontend_code_contents(&file_path);
+        let api_client_contents: String = format!("{}\n{}",
+          read_frontend_code_contents(&agent.workspace, API_TYPES_PATH),
+          read_frontend_code_contents(&agent.workspace, API_CLIENT_PATH));
 
         let page_api_endpoints = agent.buildsheet.api_assignments
-          .as_ref().unwrap().get(page_name);
+          .as_ref().and_then(|api_assignments| api_assignments.get(page_name));
 
-        let page_description: String = agent.buildsheet.pages_descriptons.as_ref()
-          .unwrap()[page_index].suggested_content_sections.to_string();
+        let page_description: String = agent.buildsheet.pages_descriptons.iter().flatten()
+          .find(|page| &page.page_name == page_name)
+          .map(|page| page.suggested_content_sections.to_string())
+          .unwrap_or_default();
 
         // Initialize Page HTML Content and Wireframe
         let msg_context: String = format!("WEBSITE SPECIFICATION: {{
@@ -171,18 +229,19 @@ impl BuildComponent {
           get_function_string!(print_html_webpage_content_with_text), 
           print_html_webpage_content_with_text).await;
 
-        // Initialize Page API Hook Integration
+        // Initialize Page API Client Integration
         let msg_context: String = format!("API_ROUTES: {{
+          COMPONENT_NAME: {},
           API_ENDPOINTS_RELATED_TO_COMPONENT: {:?},
-          REACT_HOOK_API_ENDPOINTS: {:?},
-        }}", page_api_endpoints, react_hook_contents);
+          API_CLIENT: {:?},
+        }}", component_name, page_api_endpoints, api_client_contents);
 
         // React API Display Content
         let react_api_component_content: String = ai_task_request(
           msg_context, 
           "Component Page Writer", 
-          get_function_string!(print_create_react_component_with_API_integration), 
-          print_create_react_component_with_API_integration).await;
+          get_function_string!(print_create_react_component_with_api_integration), 
+          print_create_react_component_with_api_integration).await;
 
         // Initialize create full react component
         let msg_context: String = format!("API_COMPONENT: {} HTML_WIREFRAME: {},
@@ -200,10 +259,24 @@ impl BuildComponent {
 
         // Create Component
         self.create_and_save(
+          agent,
           msg_context,
+          get_function_string!(print_give_component_fantastic_styling),
           print_give_component_fantastic_styling
         ).await;
       },
+
+      Self::AppShell => {
+
+        // Generate router, page links and 404 page for every page
+        let app_shell: AppShell = generate_app_shell(pages);
+
+        // Save and commit app shell
+        save_frontend_code(&agent.workspace, PAGE_LINKS_PATH, &app_shell.page_links);
+        save_frontend_code(&agent.workspace, NOT_FOUND_PATH, &app_shell.not_found);
+        save_frontend_code(&agent.workspace, CONTROLLER_PATH, &app_shell.controller);
+        record_step(&agent.workspace, &agent.attributes.position, "generate_app_shell", &agent.attributes.state);
+      },
     };
   
 
@@ -211,3 +284,38 @@ impl BuildComponent {
 
 
 }
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+
+  #[test]
+  fn builds_one_component_per_page() {
+    let pages: Vec<String> = vec!["home_page", "progress-dashboard", "footer"].into_iter().map(String::from).collect();
+    let components: Vec<BuildComponent> = BuildComponent::build_order(&pages);
+    assert_eq!(components.len(), 8);
+    assert_eq!(components[3], BuildComponent::ApiClient);
+    assert_eq!(components[5].filepath(), "/src/components/pages/ProgressDashboard.tsx");
+    assert_eq!(components[6].name(), "Footer2");
+    assert_eq!(components[7], BuildComponent::AppShell);
+    assert_eq!(BuildComponent::build_order(&[]).len(), 5);
+  }
+
+  #[test]
+  fn selects_components_to_build() {
+    let components: Vec<BuildComponent> = BuildComponent::build_order(&["home_page".to_string(), "about_page".to_string()]);
+    let built = |selection: &ComponentSelection| components.iter()
+      .filter(|component| selection.includes(component))
+      .map(BuildComponent::name)
+      .collect::<Vec<String>>();
+
+    assert_eq!(built(&ComponentSelection::default()).len(), 7);
+    let only: ComponentSelection = ComponentSelection { only: vec!["Logo".to_string(), "navheader".to_string()], skip: vec![] };
+    assert_eq!(built(&only), vec!["Logo", "NavHeader"]);
+    let skip: ComponentSelection = ComponentSelection { only: vec!["PageContent".to_string(), "AppShell".to_string()], skip: vec!["AboutPage".to_string(), "ReactHook".to_string()] };
+    assert_eq!(built(&skip), vec!["HomePage", "AppShell"]);
+    assert_eq!(skip.unmatched(&components), vec!["ReactHook"]);
+  }
+}
@@ -1,18 +1,39 @@
 use crate::models::agent_basic::basic_agent::BasicAgent;
-use crate::models::agents_manager::managing_agent::ManagingAgent;
+use crate::helpers::storage::StorageProfile;
 use async_trait::async_trait;
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
+use serde_json::{Map, Value};
 use std::fmt::Debug;
-use std::any::Any;
+
+
+// Accepts a bool, or the "true" / "false" strings schemas saved by earlier runs used
+fn bool_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+  match Value::deserialize(deserializer)? {
+    Value::Bool(value) => Ok(value),
+    Value::String(value) => value.trim().parse::<bool>().map_err(serde::de::Error::custom),
+    other => Err(serde::de::Error::custom(format!("expected a bool, found {}", other)))
+  }
+}
 
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct RouteObject {
-  pub is_route_dynamic: String,
+  #[serde(deserialize_with = "bool_or_string")]
+  pub is_route_dynamic: bool,
   pub method: String,
-  pub request_body: serde_json::Value,
-  pub response: serde_json::Value,
+  pub request_body: Value,
+  pub response: Value,
   pub route: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  #[serde(default)]
+  pub is_protected: bool,
+  #[serde(default, skip_serializing_if = "Map::is_empty")]
+  pub path_params: Map<String, Value>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub request_type: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub response_type: Option<String>,
 }
 
 
@@ -21,6 +42,8 @@ pub struct ProjectScope {
   pub is_crud_required: bool,
   pub is_user_login_and_logout: bool,
   pub is_external_urls_required: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub storage: Option<StorageProfile>,
 }
 
 
@@ -31,17 +54,66 @@ pub struct FactSheet {
   pub external_urls: Option<Vec<String>>,
   pub backend_code: Option<String>,
   pub api_endpoint_schema: Option<Vec<RouteObject>>,
+  pub brand_colours: Option<Vec<String>>,
+}
+
+
+// Individual FactSheet items which agents read from and produce
+// Used by the manager to work out which agents depend on which
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FactSheetField {
+  ProjectDescription,
+  ProjectScope,
+  ExternalUrls,
+  BackendCode,
+  ApiEndpointSchema,
+  BrandColours,
+}
+
+impl FactSheet {
+
+  // Confirms whether a field has been filled in yet
+  pub fn has_field(&self, field: &FactSheetField) -> bool {
+    match field {
+      FactSheetField::ProjectDescription => !self.project_description.is_empty(),
+      FactSheetField::ProjectScope => self.project_scope.is_some(),
+      FactSheetField::ExternalUrls => self.external_urls.is_some(),
+      FactSheetField::BackendCode => self.backend_code.is_some(),
+      FactSheetField::ApiEndpointSchema => self.api_endpoint_schema.is_some(),
+      FactSheetField::BrandColours => self.brand_colours.is_some(),
+    }
+  }
+
+  // Copies the given fields across from a factsheet an agent has worked on
+  pub fn merge_fields(&mut self, other: &FactSheet, fields: &[FactSheetField]) {
+    for field in fields {
+      match field {
+        FactSheetField::ProjectDescription => self.project_description = other.project_description.clone(),
+        FactSheetField::ProjectScope => self.project_scope = other.project_scope,
+        FactSheetField::ExternalUrls => self.external_urls = other.external_urls.clone(),
+        FactSheetField::BackendCode => self.backend_code = other.backend_code.clone(),
+        FactSheetField::ApiEndpointSchema => self.api_endpoint_schema = other.api_endpoint_schema.clone(),
+        FactSheetField::BrandColours => self.brand_colours = other.brand_colours.clone(),
+      }
+    }
+  }
 }
 
 
 // Trait functionality
 // This will be applied to each agent uniquely
 #[async_trait]
-pub trait SpecialFunctions: Debug {
+pub trait SpecialFunctions: Debug + Send {
     
     // Used so that manager can get attributes info from Agents
     fn get_attributes_from_agent(&self) -> &BasicAgent;
+
+    // FactSheet fields which must be filled in before the agent can start
+    fn reads(&self) -> Vec<FactSheetField>;
+
+    // FactSheet fields the agent fills in for other agents to use
+    fn produces(&self) -> Vec<FactSheetField>;
     
     // The function in which all agents will execute their logic in
-    async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error>>;
+    async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error + Send>>;
 }
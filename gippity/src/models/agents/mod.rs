@@ -1,4 +1,5 @@
 pub mod agent_backend;
+pub mod agent_brand;
 pub mod agent_frontend;
 pub mod agent_frontend_comp;
 pub mod agent_traits;
@@ -0,0 +1,311 @@
+use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet, FactSheetField};
+use crate::helpers::command_line::PrintCommand;
+use serde::{Serialize, Deserialize};
+use std::any::Any;
+use std::future::poll_fn;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::task::Poll;
+use tokio::task::JoinSet;
+
+
+// How an agent got on when the graph was run
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum AgentOutcome {
+  Completed,
+  Failed(String),
+  Refused(Vec<FactSheetField>),
+}
+
+
+// Agent handed back from its task along with its copy of the factsheet
+type FinishedAgent = (usize, Box<dyn SpecialFunctions>, FactSheet, Result<(), String>);
+
+
+// Message a panicking agent gave, for recording as its failure
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+  match payload.downcast::<String>() {
+    Ok(message) => *message,
+    Err(payload) => payload.downcast_ref::<&str>().map(|message| message.to_string()).unwrap_or("unknown panic".to_string())
+  }
+}
+
+
+// Runs an agent, turning a panic into an error so the agent is handed back and the rest of the graph keeps going
+async fn execute_agent(agent: &mut Box<dyn SpecialFunctions>, factsheet: &mut FactSheet) -> Result<(), String> {
+  let mut execution = agent.execute(factsheet);
+  poll_fn(|cx| match catch_unwind(AssertUnwindSafe(|| execution.as_mut().poll(cx))) {
+    Ok(Poll::Ready(agent_res)) => Poll::Ready(agent_res.map_err(|e| e.to_string())),
+    Ok(Poll::Pending) => Poll::Pending,
+    Err(payload) => Poll::Ready(Err(format!("panicked: {}", panic_message(payload))))
+  }).await
+}
+
+
+// Works out which agents each agent depends on
+// An agent depends on any other agent which produces a field it reads
+pub fn build_dependencies(agents: &[Box<dyn SpecialFunctions>]) -> Result<Vec<Vec<usize>>, String> {
+  let mut dependencies: Vec<Vec<usize>> = vec![vec![]; agents.len()];
+  for (i, agent) in agents.iter().enumerate() {
+    for field in agent.reads() {
+      for (j, producer) in agents.iter().enumerate() {
+        if i != j && producer.produces().contains(&field) && !dependencies[i].contains(&j) {
+          dependencies[i].push(j);
+        }
+      }
+    }
+  }
+
+  // Guard: Ensure graph has no cycles (Kahn's algorithm)
+  let mut remaining: Vec<usize> = dependencies.iter().map(|deps| deps.len()).collect();
+  let mut queue: Vec<usize> = (0..agents.len()).filter(|i| remaining[*i] == 0).collect();
+  let mut visited: usize = 0;
+  while let Some(node) = queue.pop() {
+    visited += 1;
+    for (i, deps) in dependencies.iter().enumerate() {
+      if deps.contains(&node) {
+        remaining[i] -= 1;
+        if remaining[i] == 0 {
+          queue.push(i);
+        }
+      }
+    }
+  }
+  if visited != agents.len() {
+    let stuck: Vec<&str> = (0..agents.len()).filter(|i| remaining[*i] > 0)
+      .map(|i| agents[i].get_attributes_from_agent().position.as_str()).collect();
+    return Err(format!("Agent dependency cycle between: {:?}", stuck));
+  }
+
+  Ok(dependencies)
+}
+
+
+// Runs every agent once its dependencies are done, running independent agents concurrently
+// Each agent works on its own copy of the factsheet and only the fields it produces are merged back
+// on_agent_finished is called with the latest factsheet each time an agent finishes or is refused
+pub async fn execute_agent_graph<F: FnMut(&FactSheet, &str, &AgentOutcome)>(
+  agents: Vec<Box<dyn SpecialFunctions>>,
+  factsheet: &mut FactSheet,
+  mut on_agent_finished: F
+) -> Result<Vec<(Box<dyn SpecialFunctions>, AgentOutcome)>, String> {
+
+  // Build graph
+  let dependencies: Vec<Vec<usize>> = build_dependencies(&agents)?;
+  let mut slots: Vec<Option<Box<dyn SpecialFunctions>>> = agents.into_iter().map(Some).collect();
+  let mut outcomes: Vec<Option<AgentOutcome>> = vec![None; slots.len()];
+  let mut started: Vec<bool> = vec![false; slots.len()];
+  let mut running: JoinSet<FinishedAgent> = JoinSet::new();
+
+  loop {
+
+    // Start any agents whose dependencies have all finished
+    let mut is_progressing: bool = true;
+    while is_progressing {
+      is_progressing = false;
+      for i in 0..slots.len() {
+        if started[i] || !dependencies[i].iter().all(|dep| outcomes[*dep].is_some()) {
+          continue;
+        }
+        started[i] = true;
+        let mut agent: Box<dyn SpecialFunctions> = slots[i].take().expect("Agent already taken");
+
+        // Guard: Refuse to start agent if its inputs are missing
+        let missing: Vec<FactSheetField> = agent.reads().into_iter()
+          .filter(|field| !factsheet.has_field(field)).collect();
+        if !missing.is_empty() {
+          let refuse_msg: String = format!("Refusing to start, missing inputs: {:?}", missing);
+          PrintCommand::Issue.print_agent_message(agent.get_attributes_from_agent().position.as_str(), refuse_msg.as_str());
+          let outcome: AgentOutcome = AgentOutcome::Refused(missing);
+          on_agent_finished(factsheet, agent.get_attributes_from_agent().position.as_str(), &outcome);
+          slots[i] = Some(agent);
+          outcomes[i] = Some(outcome);
+          is_progressing = true;
+          continue;
+        }
+
+        // Run agent on its own copy of the factsheet
+        let mut agent_factsheet: FactSheet = factsheet.clone();
+        running.spawn(async move {
+          let agent_res: Result<(), String> = execute_agent(&mut agent, &mut agent_factsheet).await;
+          (i, agent, agent_factsheet, agent_res)
+        });
+      }
+    }
+
+    // Wait for the next agent to finish
+    let Some(joined) = running.join_next().await else {
+      break;
+    };
+    let (i, agent, agent_factsheet, agent_res) = joined.map_err(|e| format!("Agent task could not be joined: {}", e))?;
+
+    // Merge produced fields back into the shared factsheet
+    let outcome: AgentOutcome = match agent_res {
+      Ok(()) => {
+        factsheet.merge_fields(&agent_factsheet, &agent.produces());
+        AgentOutcome::Completed
+      },
+      Err(e) => {
+        let err_msg: String = format!("Agent failed: {}", e);
+        PrintCommand::Issue.print_agent_message(agent.get_attributes_from_agent().position.as_str(), err_msg.as_str());
+        AgentOutcome::Failed(e)
+      }
+    };
+    on_agent_finished(factsheet, agent.get_attributes_from_agent().position.as_str(), &outcome);
+    outcomes[i] = Some(outcome);
+    slots[i] = Some(agent);
+  }
+
+  // Return agents in their original order
+  Ok(slots.into_iter().zip(outcomes)
+    .map(|(agent, outcome)| (agent.expect("Agent not returned"), outcome.expect("Agent never ran")))
+    .collect())
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::models::agent_basic::basic_agent::{BasicAgent, AgentState};
+  use async_trait::async_trait;
+  use std::sync::Arc;
+  use std::time::Duration;
+  use tokio::sync::Barrier;
+
+  // Agent which waits on a shared barrier, so only passes if run alongside its partner
+  #[derive(Debug)]
+  struct MockAgent {
+    attributes: BasicAgent,
+    reads: Vec<FactSheetField>,
+    produces: Vec<FactSheetField>,
+    barrier: Option<Arc<Barrier>>,
+    panics: bool,
+  }
+
+  impl MockAgent {
+    fn new(position: &str, reads: Vec<FactSheetField>, produces: Vec<FactSheetField>) -> Self {
+      Self {
+        attributes: BasicAgent {
+          position: position.to_string(),
+          state: AgentState::Discovery
+        },
+        reads,
+        produces,
+        barrier: None,
+        panics: false
+      }
+    }
+  }
+
+  #[async_trait]
+  impl SpecialFunctions for MockAgent {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+      &self.attributes
+    }
+
+    fn reads(&self) -> Vec<FactSheetField> {
+      self.reads.clone()
+    }
+
+    fn produces(&self) -> Vec<FactSheetField> {
+      self.produces.clone()
+    }
+
+    async fn execute(&mut self, factsheet: &mut FactSheet) -> Result<(), Box<dyn std::error::Error + Send>> {
+      if let Some(barrier) = &self.barrier {
+        barrier.wait().await;
+      }
+      if self.panics {
+        panic!("Too many code failed attempts");
+      }
+      for field in &self.produces {
+        match field {
+          FactSheetField::BackendCode => factsheet.backend_code = Some(self.attributes.position.clone()),
+          FactSheetField::BrandColours => factsheet.brand_colours = Some(vec![self.attributes.position.clone()]),
+          FactSheetField::ApiEndpointSchema => factsheet.api_endpoint_schema = Some(vec![]),
+          _ => {}
+        }
+      }
+      self.attributes.state = AgentState::Finished;
+      Ok(())
+    }
+  }
+
+  fn new_factsheet() -> FactSheet {
+    FactSheet {
+      project_description: "build a website that tracks fitness".to_string(),
+      project_scope: None,
+      external_urls: None,
+      backend_code: None,
+      api_endpoint_schema: None,
+      brand_colours: None
+    }
+  }
+
+  #[tokio::test]
+  async fn runs_independent_agents_concurrently() {
+    let barrier: Arc<Barrier> = Arc::new(Barrier::new(2));
+    let mut backend = MockAgent::new("Backend", vec![FactSheetField::ProjectDescription], vec![FactSheetField::BackendCode]);
+    let mut brand = MockAgent::new("Brand", vec![FactSheetField::ProjectDescription], vec![FactSheetField::BrandColours]);
+    backend.barrier = Some(barrier.clone());
+    brand.barrier = Some(barrier);
+    let frontend = MockAgent::new("Frontend", vec![FactSheetField::BackendCode, FactSheetField::BrandColours], vec![]);
+
+    // Frontend listed first to confirm order comes from the graph
+    let agents: Vec<Box<dyn SpecialFunctions>> = vec![Box::new(frontend), Box::new(backend), Box::new(brand)];
+    let mut factsheet: FactSheet = new_factsheet();
+    let results = tokio::time::timeout(Duration::from_secs(5), execute_agent_graph(agents, &mut factsheet, |_, _, _| {})).await
+      .expect("Independent agents did not run concurrently")
+      .expect("Failed to run graph");
+
+    assert!(results.iter().all(|(_, outcome)| *outcome == AgentOutcome::Completed));
+    assert_eq!(factsheet.backend_code, Some("Backend".to_string()));
+    assert_eq!(factsheet.brand_colours, Some(vec!["Brand".to_string()]));
+  }
+
+  #[tokio::test]
+  async fn refuses_agent_with_missing_inputs() {
+    let scope = MockAgent::new("Architect", vec![FactSheetField::ProjectDescription], vec![FactSheetField::ExternalUrls]);
+    let backend = MockAgent::new("Backend", vec![FactSheetField::ProjectScope], vec![FactSheetField::BackendCode]);
+    let frontend = MockAgent::new("Frontend", vec![FactSheetField::BackendCode], vec![]);
+
+    let agents: Vec<Box<dyn SpecialFunctions>> = vec![Box::new(scope), Box::new(backend), Box::new(frontend)];
+    let mut factsheet: FactSheet = new_factsheet();
+    let mut reported: Vec<String> = vec![];
+    let results = execute_agent_graph(agents, &mut factsheet, |_, position, _| reported.push(position.to_string())).await
+      .expect("Failed to run graph");
+
+    assert_eq!(results[0].1, AgentOutcome::Completed);
+    assert_eq!(results[1].1, AgentOutcome::Refused(vec![FactSheetField::ProjectScope]));
+    assert_eq!(results[2].1, AgentOutcome::Refused(vec![FactSheetField::BackendCode]));
+    assert_eq!(results[2].0.get_attributes_from_agent().state, AgentState::Discovery);
+    assert_eq!(reported, vec!["Backend", "Frontend", "Architect"]);
+  }
+
+  #[tokio::test]
+  async fn records_panicking_agent_as_failed() {
+    let mut backend = MockAgent::new("Backend", vec![FactSheetField::ProjectDescription], vec![FactSheetField::BackendCode]);
+    backend.panics = true;
+    let brand = MockAgent::new("Brand", vec![FactSheetField::ProjectDescription], vec![FactSheetField::BrandColours]);
+    let frontend = MockAgent::new("Frontend", vec![FactSheetField::BackendCode], vec![]);
+
+    let agents: Vec<Box<dyn SpecialFunctions>> = vec![Box::new(backend), Box::new(brand), Box::new(frontend)];
+    let mut factsheet: FactSheet = new_factsheet();
+    let mut reported: Vec<String> = vec![];
+    let results = execute_agent_graph(agents, &mut factsheet, |_, position, _| reported.push(position.to_string())).await
+      .expect("Failed to run graph");
+
+    assert_eq!(results[0].1, AgentOutcome::Failed("panicked: Too many code failed attempts".to_string()));
+    assert_eq!(results[1].1, AgentOutcome::Completed);
+    assert_eq!(results[2].1, AgentOutcome::Refused(vec![FactSheetField::BackendCode]));
+    assert_eq!(reported.len(), 3);
+  }
+
+  #[test]
+  fn detects_dependency_cycles() {
+    let first = MockAgent::new("First", vec![FactSheetField::BackendCode], vec![FactSheetField::BrandColours]);
+    let second = MockAgent::new("Second", vec![FactSheetField::BrandColours], vec![FactSheetField::BackendCode]);
+    let agents: Vec<Box<dyn SpecialFunctions>> = vec![Box::new(first), Box::new(second)];
+    assert!(build_dependencies(&agents).is_err());
+  }
+}
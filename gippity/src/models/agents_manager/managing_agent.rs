@@ -3,10 +3,65 @@ use crate::models::agents::agent_traits::{SpecialFunctions, FactSheet};
 use crate::models::agents::agent_architect::AgentSolutionArchitect;
 use crate::models::agents::agent_backend::AgentBackendDeveloper;
 use crate::models::agents::agent_frontend::AgentFrontendDeveloper;
+use crate::models::agents::agent_frontend_comp::ComponentSelection;
+use crate::models::agents::agent_brand::AgentBrandDesigner;
+use crate::models::agents_manager::agent_graph::{execute_agent_graph, AgentOutcome};
+use crate::models::agents_manager::run_record::RunRecord;
+use crate::helpers::command_line::PrintCommand;
 use crate::models::general::llm::Message;
 use crate::ai_functions::aifunc_managing::convert_user_input_to_goal;
 use crate::helpers::general::extend_ai_function;
-use crate::apis::call_request::call_gpt;
+use crate::apis::call_request::{call_gpt, configure_llm, llm_calls_made, restore_llm_calls, LlmSettings};
+use crate::helpers::workspace::{Workspace, WorkspaceConfig};
+use crate::helpers::scaffold::scaffold_project;
+use crate::helpers::sandbox::{detect_sandbox, Sandbox};
+use crate::helpers::approval::{ApprovalPolicy, Approver};
+use crate::helpers::quality_gates::QualityGate;
+use crate::helpers::storage::StorageProfile;
+use std::path::PathBuf;
+use std::sync::Arc;
+use crate::helpers::git::{init_project_repo, record_step};
+
+
+// Which part of the project the manager should work on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectStage {
+  Scope,
+  Backend,
+  Frontend,
+  Full
+}
+
+
+// Options chosen for a run
+#[derive(Debug, Clone)]
+pub struct RunSettings {
+  pub run_id: String,
+  pub workspace: Workspace,
+  pub sandbox: Arc<dyn Sandbox>,
+  pub approval: ApprovalPolicy,
+  pub gates: Vec<QualityGate>,
+  pub vendor_dir: Option<PathBuf>,
+  pub storage: Option<StorageProfile>,
+  pub components: ComponentSelection,
+}
+
+impl Default for RunSettings {
+  fn default() -> Self {
+    // Each run gets its own project, as on the command line, rather than the checked-in website
+    let run_id: String = RunRecord::new_run_id();
+    Self {
+      workspace: Workspace::for_run(&WorkspaceConfig::default().projects_dir(), &run_id),
+      run_id,
+      sandbox: detect_sandbox(false).expect("No sandbox for AI written code"),
+      approval: ApprovalPolicy::Interactive,
+      gates: vec![],
+      vendor_dir: None,
+      storage: None,
+      components: ComponentSelection::default()
+    }
+  }
+}
 
 
 #[derive(Debug)]
@@ -14,19 +69,20 @@ pub struct ManagingAgent {
   attributes: BasicAgent,
   factsheet: FactSheet,
   agents: Vec<Box<dyn SpecialFunctions>>,
+  run: RunRecord,
+  settings: RunSettings,
+  approver: Approver,
 }
 
 impl ManagingAgent {
 
   // Create new instance of managing agent
-  pub async fn new(usr_req: String) -> Result<Self, Box<dyn std::error::Error>> {
+  pub async fn new(usr_req: String, settings: RunSettings) -> Result<Self, Box<dyn std::error::Error>> {
 
     // Define attributes
     let attributes: BasicAgent = BasicAgent {
-      objective: "manage agents who are building a website for an end user".to_string(),
       position: "Project Manager".to_string(),
-      state: AgentState::Discovery,
-      memory: vec![]
+      state: AgentState::Discovery
     };
 
     // Convert AI Function to Goal
@@ -51,24 +107,86 @@ impl ManagingAgent {
       project_scope: None,
       external_urls: None,
       backend_code: None,
-      api_endpoint_schema: None
+      api_endpoint_schema: None,
+      brand_colours: None
     };
 
+    // Initialize run record
+    let llm_settings: &LlmSettings = configure_llm(None, None);
+    let run: RunRecord = RunRecord {
+      run_id: settings.run_id.clone(),
+      user_request: usr_req,
+      model: llm_settings.model.clone(),
+      budget: llm_settings.budget,
+      llm_calls_made: llm_calls_made(),
+      approval_policy: settings.approval,
+      gates: settings.gates.clone(),
+      storage: settings.storage,
+      factsheet: factsheet.clone(),
+      agents: vec![],
+      approvals: vec![]
+    };
+    let approver: Approver = Approver::new(settings.approval, &run.approvals_dir(&settings.workspace.root));
+
     // Return Self
     Ok(Self {
       attributes,
       factsheet,
-      agents
+      agents,
+      run,
+      settings,
+      approver
     })
   }
 
 
+  // Pick up a previously saved run
+  pub fn from_run(mut run: RunRecord, settings: RunSettings) -> Self {
+    let attributes: BasicAgent = BasicAgent {
+      position: "Project Manager".to_string(),
+      state: AgentState::Discovery
+    };
+
+    // Keep counting model calls against the run's budget from where the run left off
+    restore_llm_calls(run.llm_calls_made);
+
+    // Apply this session's approval policy, gates and storage, keeping earlier decisions
+    run.approval_policy = settings.approval;
+    run.gates = settings.gates.clone();
+    run.storage = settings.storage;
+    let approver: Approver = Approver::new(settings.approval, &run.approvals_dir(&settings.workspace.root))
+      .with_records(run.approvals.clone());
+
+    Self {
+      attributes,
+      factsheet: run.factsheet.clone(),
+      agents: vec![],
+      run,
+      settings,
+      approver
+    }
+  }
+
+
+  // Run id used for saving progress
+  pub fn run_id(&self) -> &str {
+    &self.run.run_id
+  }
+
+
   // Private: Creates an instance of all agents
-  // Important: Creates agents in order of project task execution
-  fn create_agents(&mut self) {
-    self.add_agent(Box::new(AgentSolutionArchitect::new()));
-    self.add_agent(Box::new(AgentBackendDeveloper::new()));
-    self.add_agent(Box::new(AgentFrontendDeveloper::new()));
+  // Execution order is worked out from what each agent reads and produces
+  fn create_agents(&mut self, stage: ProjectStage) {
+    if matches!(stage, ProjectStage::Scope | ProjectStage::Full) {
+      self.add_agent(Box::new(AgentSolutionArchitect::new()));
+    }
+    if matches!(stage, ProjectStage::Backend | ProjectStage::Full) {
+      self.add_agent(Box::new(AgentBackendDeveloper::new(self.settings.workspace.clone(), self.settings.sandbox.clone(), self.approver.clone(), self.settings.gates.clone(), self.settings.vendor_dir.clone(), self.settings.storage)));
+    }
+    if matches!(stage, ProjectStage::Frontend | ProjectStage::Full) {
+      self.add_agent(Box::new(AgentBrandDesigner::new()));
+      self.add_agent(Box::new(AgentFrontendDeveloper::new(self.settings.workspace.clone(), self.settings.sandbox.clone(), self.settings.components.clone())));
+    }
   }
 
   // Private: Adds an agent
@@ -76,22 +194,79 @@ impl ManagingAgent {
     self.agents.push(agent);
   }
 
-  // Public: Creates and manages project
-  pub async fn execute_project(&mut self) {
+  // Public: Creates and manages project (or a single stage of it)
+  pub async fn execute_project(&mut self, stage: ProjectStage) {
+    self.prepare_workspace();
+    self.create_agents(stage);
+    self.execute_agents().await;
+  }
 
-    // Create agents
-    self.create_agents();
+  // Public: Continues a saved run, skipping agents which already completed
+  pub async fn resume_project(&mut self) {
+    self.prepare_workspace();
+    self.create_agents(ProjectStage::Full);
+    let run: &RunRecord = &self.run;
+    self.agents.retain(|agent| !run.is_completed(agent.get_attributes_from_agent().position.as_str()));
+    self.execute_agents().await;
+  }
 
-    // Execute program for each agent
-    for agent in &mut self.agents {
+  // Private: Makes sure agents have a standalone project to work in, with the absolute paths tools report errors with
+  fn prepare_workspace(&mut self) {
+    let scaffolded: Vec<std::path::PathBuf> = scaffold_project(&self.settings.workspace).expect("Failed to scaffold project");
+    if !scaffolded.is_empty() {
+      let scaffold_msg: String = format!("Scaffolded {} template files into {:?}", scaffolded.len(), self.settings.workspace.root);
+      PrintCommand::AICall.print_agent_message(self.attributes.position.as_str(), scaffold_msg.as_str());
+    }
+    self.settings.workspace = self.settings.workspace.canonicalize().expect("Failed to resolve workspace folders");
+  }
 
-      // Handle if Solutions Architect
-      let agent_res: Result<(), Box<dyn std::error::Error>> = agent.execute(&mut self.factsheet).await;
+  // Private: Executes created agents, saving the run as each agent finishes
+  async fn execute_agents(&mut self) {
+    self.attributes.state = AgentState::Working;
 
-      // if agent.get_attributes_from_agent().position == "URL Manager" {
-      //   break;
-      // }
+    // Track each agent step in the project's own git history
+    let is_repo: bool = init_project_repo(&self.settings.workspace).unwrap_or_else(|e| {
+      let git_msg: String = format!("Agent steps will not be committed: {}", e);
+      PrintCommand::Issue.print_agent_message(self.attributes.position.as_str(), git_msg.as_str());
+      false
+    });
+    if is_repo {
+      record_step(&self.settings.workspace, &self.attributes.position, get_function_string!(scaffold_project), &self.attributes.state);
     }
+    let sandbox_msg: String = format!("AI written code will run in the {} sandbox", self.settings.sandbox.name());
+    PrintCommand::AICall.print_agent_message(self.attributes.position.as_str(), sandbox_msg.as_str());
+    self.run.save(&self.settings.workspace.root).expect("Failed to save run");
+
+    // Execute agents as their inputs become available
+    let agents: Vec<Box<dyn SpecialFunctions>> = std::mem::take(&mut self.agents);
+    let run: &mut RunRecord = &mut self.run;
+    let workspace: &Workspace = &self.settings.workspace;
+    let approver: &Approver = &self.approver;
+    let agent_results: Vec<(Box<dyn SpecialFunctions>, AgentOutcome)> = execute_agent_graph(agents, &mut self.factsheet, |factsheet, position, outcome| {
+      run.factsheet = factsheet.clone();
+      run.set_outcome(position, outcome);
+      run.approvals = approver.records();
+      run.llm_calls_made = llm_calls_made();
+      run.save(&workspace.root).expect("Failed to save run");
+    }).await.expect("Failed to schedule agents");
+
+    // Report outcome for each agent
+    for (agent, outcome) in agent_results {
+      let outcome_msg: String = format!("Outcome: {:?}", outcome);
+      let print_command: PrintCommand = match outcome {
+        AgentOutcome::Completed => PrintCommand::UnitTest,
+        _ => PrintCommand::Issue
+      };
+      print_command.print_agent_message(agent.get_attributes_from_agent().position.as_str(), outcome_msg.as_str());
+      self.agents.push(agent);
+    }
+
+    // Report model usage against budget
+    self.run.llm_calls_made = llm_calls_made();
+    self.run.save(&self.settings.workspace.root).expect("Failed to save run");
+    let usage_msg: String = format!("Run {} used {} LLM calls (budget: {:?})", self.run.run_id, llm_calls_made(), self.run.budget);
+    PrintCommand::AICall.print_agent_message(self.attributes.position.as_str(), usage_msg.as_str());
+    self.attributes.state = AgentState::Finished;
   }
 }
 
@@ -104,7 +279,7 @@ pub mod tests {
   #[tokio::test]
   async fn creates_new_managing_agent() {
     let usr_request: &str = "need a website that looks great and handles storing user data";
-    let managing_agent = ManagingAgent::new(usr_request.to_string()).await.expect("Error creating agent");
+    let managing_agent = ManagingAgent::new(usr_request.to_string(), RunSettings::default()).await.expect("Error creating agent");
     dbg!(&managing_agent);
     assert_eq!(managing_agent.attributes.position, "Project Manager")
   }
@@ -112,11 +287,11 @@ pub mod tests {
   #[tokio::test]
   async fn executes_building_a_website() {
     let usr_request: &str = "need a full stack app that fetches and tracks my fitness progress. Needs to include timezone info from the web.";
-    let mut managing_agent: ManagingAgent = ManagingAgent::new(usr_request.to_string()).await.expect("Error creating agent");
+    let mut managing_agent: ManagingAgent = ManagingAgent::new(usr_request.to_string(), RunSettings::default()).await.expect("Error creating agent");
 
-    managing_agent.execute_project().await;
+    managing_agent.execute_project(ProjectStage::Full).await;
 
-    let encoded_factsheet: String = serde_json::to_string(&managing_agent.factsheet).unwrap();
+    let _encoded_factsheet: String = serde_json::to_string(&managing_agent.factsheet).unwrap();
 
     // println!("{:?}", encoded_factsheet);
   }
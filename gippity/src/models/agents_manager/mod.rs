@@ -1 +1,3 @@
-pub mod managing_agent;
\ No newline at end of file
+pub mod agent_graph;
+pub mod managing_agent;
+pub mod run_record;
\ No newline at end of file
@@ -0,0 +1,148 @@
+use crate::models::agents::agent_traits::FactSheet;
+use crate::models::agents_manager::agent_graph::AgentOutcome;
+use crate::helpers::approval::{ApprovalPolicy, ApprovalRecord};
+use crate::helpers::quality_gates::QualityGate;
+use crate::helpers::storage::StorageProfile;
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+
+// Outcome of an agent against its position
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AgentRecord {
+  pub position: String,
+  pub outcome: AgentOutcome,
+}
+
+
+// Everything needed to inspect or resume a run
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunRecord {
+  pub run_id: String,
+  pub user_request: String,
+  pub model: String,
+  pub budget: Option<u32>,
+  #[serde(default)]
+  pub llm_calls_made: u32,
+  #[serde(default)]
+  pub approval_policy: ApprovalPolicy,
+  #[serde(default)]
+  pub gates: Vec<QualityGate>,
+  #[serde(default)]
+  pub storage: Option<StorageProfile>,
+  pub factsheet: FactSheet,
+  pub agents: Vec<AgentRecord>,
+  #[serde(default)]
+  pub approvals: Vec<ApprovalRecord>,
+}
+
+impl RunRecord {
+
+  // Creates a new run id based on the current time
+  pub fn new_run_id() -> String {
+    let secs: u64 = SystemTime::now().duration_since(UNIX_EPOCH)
+      .expect("System time before unix epoch").as_secs();
+    format!("run-{}", secs)
+  }
+
+  // Folder all runs are saved in
+  pub fn runs_dir(workspace: &Path) -> PathBuf {
+    workspace.join(".gippity").join("runs")
+  }
+
+  // Folder external reviewers approve code in
+  pub fn approvals_dir(&self, workspace: &Path) -> PathBuf {
+    Self::runs_dir(workspace).join(format!("{}-approvals", self.run_id))
+  }
+
+  // Update or add an agents outcome
+  pub fn set_outcome(&mut self, position: &str, outcome: &AgentOutcome) {
+    match self.agents.iter_mut().find(|record| record.position == position) {
+      Some(record) => record.outcome = outcome.clone(),
+      None => self.agents.push(AgentRecord { position: position.to_string(), outcome: outcome.clone() })
+    }
+  }
+
+  // Confirms whether an agent already completed in this run
+  pub fn is_completed(&self, position: &str) -> bool {
+    self.agents.iter().any(|record| record.position == position && record.outcome == AgentOutcome::Completed)
+  }
+
+  // Save run to workspace
+  pub fn save(&self, workspace: &Path) -> std::io::Result<()> {
+    let runs_dir: PathBuf = Self::runs_dir(workspace);
+    fs::create_dir_all(&runs_dir)?;
+    let contents: String = serde_json::to_string_pretty(self)?;
+    fs::write(runs_dir.join(format!("{}.json", self.run_id)), contents)
+  }
+
+  // Load a run from workspace
+  pub fn load(workspace: &Path, run_id: &str) -> std::io::Result<Self> {
+    let path: PathBuf = Self::runs_dir(workspace).join(format!("{}.json", run_id));
+    let contents: String = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+  }
+
+  // Load the most recent run from workspace
+  pub fn load_latest(workspace: &Path) -> std::io::Result<Self> {
+    let latest_run_id: Option<String> = fs::read_dir(Self::runs_dir(workspace))?
+      .filter_map(|entry| entry.ok())
+      .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.strip_suffix(".json")).map(String::from))
+      .max();
+    match latest_run_id {
+      Some(run_id) => Self::load(workspace, &run_id),
+      None => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "No runs found in workspace"))
+    }
+  }
+}
+
+
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::models::agents::agent_traits::FactSheetField;
+
+  #[test]
+  fn saves_and_loads_latest_run() {
+    let workspace: PathBuf = std::env::temp_dir().join(format!("gippity-runs-{}", std::process::id()));
+    let factsheet: FactSheet = serde_json::from_str("{\"project_description\":\"build a todo app\",\"project_scope\":null,\"external_urls\":null,\"backend_code\":null,\"api_endpoint_schema\":null}").unwrap();
+    let mut record: RunRecord = RunRecord {
+      run_id: "run-100".to_string(),
+      user_request: "todo app".to_string(),
+      model: "gpt-4".to_string(),
+      budget: Some(10),
+      llm_calls_made: 7,
+      approval_policy: ApprovalPolicy::AutoApproveIfClean,
+      gates: vec![QualityGate::Clippy],
+      storage: Some(StorageProfile::Sqlite),
+      factsheet,
+      agents: vec![],
+      approvals: vec![]
+    };
+
+    // Record outcomes, replacing earlier outcomes for the same agent
+    record.set_outcome("Solutions Architect", &AgentOutcome::Failed("timeout".to_string()));
+    record.set_outcome("Solutions Architect", &AgentOutcome::Completed);
+    record.set_outcome("Backend Developer", &AgentOutcome::Refused(vec![FactSheetField::ProjectScope]));
+    record.save(&workspace).unwrap();
+
+    // Older run should not be picked up as latest
+    let mut older: RunRecord = record.clone();
+    older.run_id = "run-099".to_string();
+    older.save(&workspace).unwrap();
+
+    let loaded: RunRecord = RunRecord::load_latest(&workspace).unwrap();
+    fs::remove_dir_all(&workspace).unwrap();
+    assert_eq!(loaded.run_id, "run-100");
+    assert_eq!(loaded.agents.len(), 2);
+    assert_eq!(loaded.llm_calls_made, 7);
+    assert_eq!(loaded.approval_policy, ApprovalPolicy::AutoApproveIfClean);
+    assert_eq!(loaded.gates, vec![QualityGate::Clippy]);
+    assert_eq!(loaded.storage, Some(StorageProfile::Sqlite));
+    assert!(loaded.is_completed("Solutions Architect"));
+    assert!(!loaded.is_completed("Backend Developer"));
+  }
+}
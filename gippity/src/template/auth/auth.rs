@@ -0,0 +1,104 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::error::{ErrorInternalServerError, ErrorUnauthorized};
+use actix_web::http::header;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+// How long a login lasts
+const TOKEN_LIFETIME_SECS: u64 = 60 * 60 * 24;
+
+// Hashes a password with a random salt, to be stored in place of the password
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+// Checks a password against a stored hash
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match PasswordHash::new(password_hash) {
+        Ok(parsed_hash) => Argon2::default().verify_password(password.as_bytes(), &parsed_hash).is_ok(),
+        Err(_) => false,
+    }
+}
+
+// Contents of a login token, passed to protected handlers as web::ReqData<Claims>
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: u64,
+    pub jti: String,
+}
+
+// Signs and checks login tokens, remembering tokens revoked by logging out
+pub struct AuthKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    revoked: Mutex<HashSet<String>>,
+}
+
+impl AuthKeys {
+    // Signing secret from JWT_SECRET, else a random one so tokens last until the server restarts
+    pub fn from_env() -> Self {
+        let secret = std::env::var("JWT_SECRET")
+            .unwrap_or_else(|_| format!("{}{}", Uuid::new_v4(), Uuid::new_v4()));
+        Self {
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            revoked: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn issue_token(&self, username: &str) -> Result<String, jsonwebtoken::errors::Error> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let claims = Claims {
+            sub: username.to_string(),
+            exp: now + TOKEN_LIFETIME_SECS,
+            jti: Uuid::new_v4().to_string(),
+        };
+        encode(&Header::default(), &claims, &self.encoding)
+    }
+
+    pub fn verify_token(&self, token: &str) -> Option<Claims> {
+        let claims = decode::<Claims>(token, &self.decoding, &Validation::default()).ok()?.claims;
+        match self.revoked.lock().unwrap().contains(&claims.jti) {
+            true => None,
+            false => Some(claims),
+        }
+    }
+
+    pub fn revoke(&self, claims: &Claims) {
+        self.revoked.lock().unwrap().insert(claims.jti.clone());
+    }
+}
+
+// Token from an "Authorization: Bearer <token>" header
+fn bearer_token(req: &ServiceRequest) -> Option<String> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    value.strip_prefix("Bearer ").map(String::from)
+}
+
+// Auth guard for protected routes, used as .wrap(actix_web::middleware::from_fn(require_auth))
+// Rejects requests without a valid token with 401 Unauthorized
+pub async fn require_auth(req: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let keys = req
+        .app_data::<web::Data<AuthKeys>>()
+        .ok_or_else(|| ErrorInternalServerError("AuthKeys missing from app data"))?;
+    let claims = bearer_token(&req)
+        .and_then(|token| keys.verify_token(&token))
+        .ok_or_else(|| ErrorUnauthorized("Missing or invalid token"))?;
+    req.extensions_mut().insert(claims);
+    next.call(req).await
+}
@@ -0,0 +1,52 @@
+// Using the auth module from the webserver's handlers and main()
+use crate::auth::{hash_password, verify_password, require_auth, AuthKeys, Claims};
+use actix_web::middleware::from_fn;
+
+// Passwords are hashed before they are stored
+async fn register(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {
+    let mut user = user.into_inner();
+    user.password = match hash_password(&user.password) {
+        Ok(password_hash) => password_hash,
+        Err(_) => return HttpResponse::InternalServerError().finish(),
+    };
+    let mut db = app_state.db.lock().unwrap();
+    db.insert_user(user);
+    let _ = db.save_to_file();
+    HttpResponse::Ok().finish()
+}
+
+// Logging in returns {"token": "..."}, sent back as "Authorization: Bearer <token>"
+async fn login(app_state: web::Data<AppState>, keys: web::Data<AuthKeys>, user: web::Json<User>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.get_user_by_name(&user.username) {
+        Some(stored_user) if verify_password(&user.password, &stored_user.password) => {
+            match keys.issue_token(&stored_user.username) {
+                Ok(token) => HttpResponse::Ok().json(serde_json::json!({ "token": token })),
+                Err(_) => HttpResponse::InternalServerError().finish(),
+            }
+        },
+        _ => HttpResponse::Unauthorized().body("Invalid username or password"),
+    }
+}
+
+// Logging out revokes the token, and claims.sub is the logged in username
+async fn logout(keys: web::Data<AuthKeys>, claims: web::ReqData<Claims>) -> impl Responder {
+    keys.revoke(&claims);
+    HttpResponse::Ok().finish()
+}
+
+// In main(): create the keys once, register and login stay public and every other route goes behind require_auth
+let keys = web::Data::new(AuthKeys::from_env());
+HttpServer::new(move || {
+    App::new()
+        .app_data(data.clone())
+        .app_data(keys.clone())
+        .route("/register", web::post().to(register))
+        .route("/login", web::post().to(login))
+        .service(
+            web::scope("")
+                .wrap(from_fn(require_auth))
+                .route("/logout", web::post().to(logout))
+                .route("/task", web::get().to(read_all_tasks))
+        )
+})
@@ -0,0 +1,14 @@
+import "./App.css";
+import Controller from "./components/Controller";
+
+function App() {
+  return (
+    <>
+      <div>
+        <Controller />
+      </div>
+    </>
+  );
+}
+
+export default App;
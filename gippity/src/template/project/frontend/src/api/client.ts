@@ -0,0 +1,3 @@
+// Generated from the backend's OpenAPI document. Do not edit, it is regenerated with the backend.
+
+export const API_BASE_URL = import.meta.env.VITE_API_BASE_URL ?? "http://localhost:8080";
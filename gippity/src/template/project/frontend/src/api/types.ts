@@ -0,0 +1 @@
+// Generated from the backend's OpenAPI document. Do not edit, it is regenerated with the backend.
@@ -0,0 +1,27 @@
+// Generated from the site's pages. Do not edit, it is regenerated with the frontend.
+import { BrowserRouter, Route, Routes } from "react-router-dom";
+import Logo from "./shared/Logo";
+import Navigation from "./shared/Navigation";
+import Footer from "./shared/Footer";
+import PageOne from "./pages/PageOne";
+import PageTwo from "./pages/PageTwo";
+import NotFound from "./pages/NotFound";
+
+function Controller() {
+  return (
+    <BrowserRouter>
+      <header className="flex justify-between items-center px-4 py-2 shadow-md">
+        <Logo />
+        <Navigation />
+      </header>
+      <Routes>
+        <Route path="/" element={<PageOne />} />
+        <Route path="/page-two" element={<PageTwo />} />
+        <Route path="*" element={<NotFound />} />
+      </Routes>
+      <Footer />
+    </BrowserRouter>
+  );
+}
+
+export default Controller;
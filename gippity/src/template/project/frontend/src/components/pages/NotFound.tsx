@@ -0,0 +1,18 @@
+// Generated from the site's pages. Do not edit, it is regenerated with the frontend.
+import { Link } from "react-router-dom";
+import Logo from "../shared/Logo";
+
+function NotFound() {
+  return (
+    <main className="flex flex-col items-center justify-center gap-4 px-4 py-16 text-center">
+      <Logo />
+      <h1 className="text-3xl font-bold">Page not found</h1>
+      <p className="text-gray-600">The page you are looking for does not exist.</p>
+      <Link to="/" className="text-blue-500 underline">
+        Back to the home page
+      </Link>
+    </main>
+  );
+}
+
+export default NotFound;
@@ -0,0 +1,5 @@
+function PageOne() {
+  return <main className="px-4 py-8">Page One</main>;
+}
+
+export default PageOne;
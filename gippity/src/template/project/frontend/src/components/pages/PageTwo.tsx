@@ -0,0 +1,5 @@
+function PageTwo() {
+  return <main className="px-4 py-8">Page Two</main>;
+}
+
+export default PageTwo;
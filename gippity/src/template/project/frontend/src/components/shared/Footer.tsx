@@ -0,0 +1,5 @@
+function Footer() {
+  return <footer className="px-4 py-2 text-sm text-gray-500">Footer</footer>;
+}
+
+export default Footer;
@@ -0,0 +1,5 @@
+function Logo() {
+  return <span className="font-bold text-xl">Logo</span>;
+}
+
+export default Logo;
@@ -0,0 +1,21 @@
+import { NavLink } from "react-router-dom";
+import { PAGE_LINKS } from "../../routes";
+
+function Navigation() {
+  return (
+    <nav>
+      {PAGE_LINKS.map(({ path, label }) => (
+        <NavLink
+          key={path}
+          to={path}
+          end
+          className={({ isActive }) => `ml-4 ${isActive ? "text-blue-500" : "text-gray-700"}`}
+        >
+          {label}
+        </NavLink>
+      ))}
+    </nav>
+  );
+}
+
+export default Navigation;
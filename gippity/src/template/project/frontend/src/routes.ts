@@ -0,0 +1,10 @@
+// Generated from the site's pages. Do not edit, it is regenerated with the frontend.
+export type PageLink = {
+  path: string;
+  label: string;
+};
+
+export const PAGE_LINKS: PageLink[] = [
+  { path: "/", label: "Page One" },
+  { path: "/page-two", label: "Page Two" },
+];
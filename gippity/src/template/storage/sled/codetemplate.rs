@@ -0,0 +1,212 @@
+use actix_cors::Cors;
+use actix_web::{http::header, web, App, HttpServer, Responder, HttpResponse};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+// Files in the migrations folder, embedded by build.rs
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Task {
+    pub id: u64,
+    pub name: String,
+    pub completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct User {
+    pub id: u64,
+    pub username: String,
+    pub password: String,
+}
+
+// sled is safe to share between threads, so no Mutex is needed
+// Each collection is a tree of JSON values keyed by a string
+struct Database {
+    db: sled::Db,
+    tasks: sled::Tree,
+    users: sled::Tree,
+}
+
+fn to_db_error(e: serde_json::Error) -> sled::Error {
+    sled::Error::Unsupported(e.to_string())
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> sled::Result<T> {
+    serde_json::from_slice(bytes).map_err(to_db_error)
+}
+
+impl Database {
+    fn open(path: &str) -> sled::Result<Self> {
+        let db = sled::open(path)?;
+        let database = Self {
+            tasks: db.open_tree("tasks")?,
+            users: db.open_tree("users")?,
+            db,
+        };
+        database.run_migrations()?;
+        Ok(database)
+    }
+
+    // DATABASE MIGRATIONS
+    // Each migrations/*.json file maps tree names to seed values by key, added in file name order when missing
+    fn run_migrations(&self) -> sled::Result<()> {
+        for (_, contents) in MIGRATIONS.iter().filter(|(name, _)| name.ends_with(".json")) {
+            let trees: serde_json::Map<String, Value> = serde_json::from_str(contents).map_err(to_db_error)?;
+            for (tree_name, seeds) in trees {
+                let tree = self.db.open_tree(tree_name)?;
+                for (key, value) in seeds.as_object().cloned().unwrap_or_default() {
+                    let seed = serde_json::to_vec(&value).map_err(to_db_error)?;
+                    let _ = tree.compare_and_swap(key.as_bytes(), None as Option<&[u8]>, Some(seed))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // PROJECT CRUD DATA RELATED
+    fn insert(&self, task: Task) -> sled::Result<()> {
+        let value = serde_json::to_vec(&task).map_err(to_db_error)?;
+        self.tasks.insert(task.id.to_string(), value)?;
+        Ok(())
+    }
+
+    fn get(&self, id: &u64) -> sled::Result<Option<Task>> {
+        match self.tasks.get(id.to_string())? {
+            Some(bytes) => Ok(Some(decode(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_all(&self) -> sled::Result<Vec<Task>> {
+        self.tasks.iter().values().map(|bytes| decode(&bytes?)).collect()
+    }
+
+    fn delete(&self, id: &u64) -> sled::Result<()> {
+        self.tasks.remove(id.to_string())?;
+        Ok(())
+    }
+
+    fn update(&self, task: Task) -> sled::Result<()> {
+        self.insert(task)
+    }
+
+    // USER DATA RELATED FUNCTIONS
+    fn insert_user(&self, user: User) -> sled::Result<()> {
+        let value = serde_json::to_vec(&user).map_err(to_db_error)?;
+        self.users.insert(user.username.as_bytes(), value)?;
+        Ok(())
+    }
+
+    fn get_user_by_name(&self, username: &str) -> sled::Result<Option<User>> {
+        match self.users.get(username.as_bytes())? {
+            Some(bytes) => Ok(Some(decode(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    // DATABASE SAVING
+    fn flush(&self) -> sled::Result<()> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+struct AppState {
+    db: Database,
+}
+
+async fn create_task(app_state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
+    let db = &app_state.db;
+    match db.insert(task.into_inner()).and_then(|_| db.flush()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn read_task(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
+    match app_state.db.get(&id.into_inner()) {
+        Ok(Some(task)) => HttpResponse::Ok().json(task),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn read_all_tasks(app_state: web::Data<AppState>) -> impl Responder {
+    match app_state.db.get_all() {
+        Ok(tasks) => HttpResponse::Ok().json(tasks),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn update_task(app_state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
+    let db = &app_state.db;
+    match db.update(task.into_inner()).and_then(|_| db.flush()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn delete_task(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
+    let db = &app_state.db;
+    match db.delete(&id.into_inner()).and_then(|_| db.flush()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn register(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {
+    let db = &app_state.db;
+    match db.insert_user(user.into_inner()).and_then(|_| db.flush()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn login(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {
+    match app_state.db.get_user_by_name(&user.username) {
+        Ok(Some(stored_user)) if stored_user.password == user.password => {
+            HttpResponse::Ok().body("Logged in!")
+        },
+        _ => HttpResponse::BadRequest().body("Invalid username or password"),
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let db = Database::open("database.sled")
+        .map_err(|e| std::io::Error::other(format!("Unable to open database: {}", e)))?;
+
+    let data = web::Data::new(AppState { db });
+
+    let port: u16 = std::env::var("PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(8080);
+
+    HttpServer::new(move || {
+        App::new()
+            .wrap(
+                Cors::permissive()
+                    .allowed_origin_fn(|origin, _req_head| {
+                        origin.as_bytes().starts_with(b"http://localhost:") || origin == "null"
+                    })
+                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+                    .allowed_headers(vec![header::AUTHORIZATION, header::ACCEPT])
+                    .allowed_header(header::CONTENT_TYPE)
+                    .supports_credentials()
+                    .max_age(3600),
+            )
+            .app_data(data.clone())
+            .route("/task", web::post().to(create_task))
+            .route("/task", web::get().to(read_all_tasks))
+            .route("/task/{id}", web::get().to(read_task))
+            .route("/task/{id}", web::put().to(update_task))
+            .route("/task/{id}", web::delete().to(delete_task))
+            .route("/register", web::post().to(register))
+            .route("/login", web::post().to(login))
+    })
+    .bind(("127.0.0.1", port))?
+    .run()
+    .await
+}
@@ -0,0 +1,220 @@
+use actix_cors::Cors;
+use actix_web::{http::header, web, App, HttpServer, Responder, HttpResponse};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+// Files in the migrations folder, embedded by build.rs
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Task {
+    pub id: u64,
+    pub name: String,
+    pub completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct User {
+    pub id: u64,
+    pub username: String,
+    pub password: String,
+}
+
+struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    fn open(path: &str) -> rusqlite::Result<Self> {
+        let db = Self {
+            conn: Connection::open(path)?,
+        };
+        db.run_migrations()?;
+        Ok(db)
+    }
+
+    // DATABASE MIGRATIONS
+    // Applies each migrations/*.sql file once, in file name order
+    fn run_migrations(&self) -> rusqlite::Result<()> {
+        self.conn.execute("CREATE TABLE IF NOT EXISTS _migrations (name TEXT PRIMARY KEY)", [])?;
+        for (name, sql) in MIGRATIONS.iter().filter(|(name, _)| name.ends_with(".sql")) {
+            let is_applied = self.conn
+                .query_row("SELECT 1 FROM _migrations WHERE name = ?1", params![name], |_| Ok(()))
+                .optional()?
+                .is_some();
+            if is_applied {
+                continue;
+            }
+            self.conn.execute_batch(sql)?;
+            self.conn.execute("INSERT INTO _migrations (name) VALUES (?1)", params![name])?;
+        }
+        Ok(())
+    }
+
+    fn task_from_row(row: &rusqlite::Row) -> rusqlite::Result<Task> {
+        Ok(Task {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            completed: row.get(2)?,
+        })
+    }
+
+    // PROJECT CRUD DATA RELATED
+    fn insert(&self, task: Task) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO tasks (id, name, completed) VALUES (?1, ?2, ?3)",
+            params![task.id, task.name, task.completed],
+        )?;
+        Ok(())
+    }
+
+    fn get(&self, id: &u64) -> rusqlite::Result<Option<Task>> {
+        self.conn
+            .query_row("SELECT id, name, completed FROM tasks WHERE id = ?1", params![id], Self::task_from_row)
+            .optional()
+    }
+
+    fn get_all(&self) -> rusqlite::Result<Vec<Task>> {
+        let mut statement = self.conn.prepare("SELECT id, name, completed FROM tasks ORDER BY id")?;
+        let tasks = statement.query_map([], Self::task_from_row)?;
+        tasks.collect()
+    }
+
+    fn delete(&self, id: &u64) -> rusqlite::Result<()> {
+        self.conn.execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn update(&self, task: Task) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO tasks (id, name, completed) VALUES (?1, ?2, ?3)",
+            params![task.id, task.name, task.completed],
+        )?;
+        Ok(())
+    }
+
+    // USER DATA RELATED FUNCTIONS
+    fn insert_user(&self, user: User) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO users (id, username, password) VALUES (?1, ?2, ?3)",
+            params![user.id, user.username, user.password],
+        )?;
+        Ok(())
+    }
+
+    fn get_user_by_name(&self, username: &str) -> rusqlite::Result<Option<User>> {
+        self.conn
+            .query_row(
+                "SELECT id, username, password FROM users WHERE username = ?1",
+                params![username],
+                |row| Ok(User { id: row.get(0)?, username: row.get(1)?, password: row.get(2)? }),
+            )
+            .optional()
+    }
+}
+
+struct AppState {
+    db: Mutex<Database>,
+}
+
+async fn create_task(app_state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.insert(task.into_inner()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
+}
+
+async fn read_task(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.get(&id.into_inner()) {
+        Ok(Some(task)) => HttpResponse::Ok().json(task),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn read_all_tasks(app_state: web::Data<AppState>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.get_all() {
+        Ok(tasks) => HttpResponse::Ok().json(tasks),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn update_task(app_state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.update(task.into_inner()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn delete_task(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.delete(&id.into_inner()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+async fn register(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.insert_user(user.into_inner()) {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
+}
+
+async fn login(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+
+    match db.get_user_by_name(&user.username) {
+        Ok(Some(stored_user)) if stored_user.password == user.password => {
+            HttpResponse::Ok().body("Logged in!")
+        },
+        _ => HttpResponse::BadRequest().body("Invalid username or password"),
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let db = Database::open("database.db")
+        .map_err(|e| std::io::Error::other(format!("Unable to open database: {}", e)))?;
+
+    let data = web::Data::new(AppState {
+        db: Mutex::new(db),
+    });
+
+    let port: u16 = std::env::var("PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(8080);
+
+    HttpServer::new(move || {
+        App::new()
+            .wrap(
+                Cors::permissive()
+                    .allowed_origin_fn(|origin, _req_head| {
+                        origin.as_bytes().starts_with(b"http://localhost:") || origin == "null"
+                    })
+                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+                    .allowed_headers(vec![header::AUTHORIZATION, header::ACCEPT])
+                    .allowed_header(header::CONTENT_TYPE)
+                    .supports_credentials()
+                    .max_age(3600),
+            )
+            .app_data(data.clone())
+            .route("/task", web::post().to(create_task))
+            .route("/task", web::get().to(read_all_tasks))
+            .route("/task/{id}", web::get().to(read_task))
+            .route("/task/{id}", web::put().to(update_task))
+            .route("/task/{id}", web::delete().to(delete_task))
+            .route("/register", web::post().to(register))
+            .route("/login", web::post().to(login))
+    })
+    .bind(("127.0.0.1", port))?
+    .run()
+    .await
+}
@@ -0,0 +1,25 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Embeds every file in the migrations folder into the server as MIGRATIONS, in file name order
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+
+    let mut names: Vec<String> = fs::read_dir("migrations")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+
+    let migrations: String = names
+        .iter()
+        .map(|name| format!("    ({:?}, include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/migrations/\", {:?}))),\n", name, name))
+        .collect();
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("migrations.rs");
+    fs::write(out_path, format!("pub const MIGRATIONS: &[(&str, &str)] = &[\n{}];\n", migrations)).unwrap();
+}
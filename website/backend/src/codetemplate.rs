@@ -6,6 +6,9 @@ use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
 
+// Files in the migrations folder, embedded by build.rs
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Task {
     pub id: u64,
@@ -77,6 +80,25 @@ impl Database {
         let db: Database = serde_json::from_str(&file_content)?;
         Ok(db)
     }
+
+    // DATABASE MIGRATIONS
+    // Each migrations/*.json file maps collection names to seed records by key, added in file name order when missing
+    fn run_migrations(&mut self) -> std::io::Result<()> {
+        let mut data = serde_json::to_value(&*self)?;
+        for (_, contents) in MIGRATIONS.iter().filter(|(name, _)| name.ends_with(".json")) {
+            let collections: serde_json::Map<String, serde_json::Value> = serde_json::from_str(contents)?;
+            for (collection_name, seeds) in collections {
+                let collection = data[&collection_name].as_object_mut();
+                if let (Some(collection), Some(seeds)) = (collection, seeds.as_object()) {
+                    for (key, seed) in seeds {
+                        collection.entry(key.clone()).or_insert(seed.clone());
+                    }
+                }
+            }
+        }
+        *self = serde_json::from_value(data)?;
+        Ok(())
+    }
 }
 
 struct AppState {
@@ -138,15 +160,21 @@ async fn login(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Re
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let db = match Database::load_from_file() {
+    let mut db = match Database::load_from_file() {
         Ok(db) => db,
         Err(_) => Database::new(),
     };
+    db.run_migrations()?;
 
     let data = web::Data::new(AppState {
         db: Mutex::new(db),
     });
 
+    let port: u16 = std::env::var("PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(8080);
+
     HttpServer::new(move || {
         App::new()
             .wrap(
@@ -169,7 +197,7 @@ async fn main() -> std::io::Result<()> {
             .route("/register", web::post().to(register))
             .route("/login", web::post().to(login))
     })
-    .bind("127.0.0.1:8080")?
+    .bind(("127.0.0.1", port))?
     .run()
     .await
 }